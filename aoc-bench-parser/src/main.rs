@@ -3,21 +3,31 @@ use std::{
     path::PathBuf,
 };
 
+use aoc_bench_results::{
+    Calibration, Event, Fingerprint, Isolation, Order, Phase, PhaseResult, RunInfo, Shard, Status,
+    Timeouts, Variant,
+};
 use clap::Parser;
 use color_eyre::eyre::{Context, Result};
 use tabled::{
     builder::Builder,
     settings::{object::Rows, Alignment, Modify, Style},
+    Table,
 };
 
 #[derive(Parser)]
 struct Args {
-    /// the directory containing the criterion output
-    #[clap(short, long)]
-    criterion_dir: PathBuf,
-    /// the log file from the benchmark run
-    #[clap(short, long)]
-    logfile: PathBuf,
+    /// the results files written by aoc-bench, or directories of them. The results of several
+    /// runs, e.g. the shards of a run, are merged into one report
+    #[clap(short, long, num_args = 1.., required = true)]
+    results: Vec<PathBuf>,
+    /// show the times as if the calibration workload had taken this many nanoseconds, to compare
+    /// runs on machines of different speed. Runs without a calibration are shown as they are
+    #[clap(long, value_name = "NS")]
+    normalize: Option<f64>,
+    /// warn about runs whose calibration time changed by more than this many percent
+    #[clap(long, default_value_t = 5.0)]
+    drift_threshold: f64,
 }
 
 #[derive(Default)]
@@ -27,75 +37,346 @@ struct AoCBenchmarks {
 
 #[derive(Default)]
 struct AoCBenchmarkDay {
-    phases: BTreeMap<String, AoCBenchmarkPhase>,
+    /// all inputs of the day that were verified or benchmarked
+    inputs: BTreeSet<String>,
+    phases: BTreeMap<Phase, AoCBenchmarkPhase>,
+}
+
+impl AoCBenchmarkDay {
+    /// Whether `user` failed any input of the day, including the inputs that were only verified
+    /// and the held-out ones.
+    /// [`Phase::Total`] is left out, it fails whenever one of the parts is not implemented.
+    fn has_failures(&self, user: &str) -> bool {
+        self.phases
+            .iter()
+            .filter(|(phase, _)| **phase != Phase::Total)
+            .filter_map(|(_, phase_benchmarks)| phase_benchmarks.results.get(user))
+            .flat_map(|inputs| inputs.values())
+            .any(|r| !matches!(r.status, Status::Ok | Status::NotImplemented))
+    }
+
+    /// Whether `user` is ranked in `phase`, i.e., passed every input of the day and has a
+    /// `metric` value for all inputs that anybody has one for.
+    fn is_ranked(&self, phase: Phase, user: &str, metric: Metric) -> bool {
+        !self.has_failures(user) && self.phases[&phase].is_complete(user, metric)
+    }
+
+    /// the fastest aggregate of the ranked users in `phase`, if there is one
+    fn min_aggregate(&self, phase: Phase, metric: Metric) -> Option<f64> {
+        let phase_benchmarks = &self.phases[&phase];
+        phase_benchmarks
+            .results
+            .keys()
+            .filter(|user| self.is_ranked(phase, user, metric))
+            .filter_map(|user| phase_benchmarks.aggregate(user, metric))
+            .min_by(|a, b| a.partial_cmp(b).unwrap())
+    }
+}
+
+/// Which of the measured times a table shows.
+#[derive(Clone, Copy)]
+enum Metric {
+    /// the median of the criterion samples
+    Warm,
+    /// the median of the cold starts in fresh processes
+    Cold,
+    /// the median number of instructions retired
+    Instructions,
+    /// the fuel consumed by the WASM build
+    Fuel,
+}
+
+impl Metric {
+    fn value(self, result: &PhaseResult) -> Option<f64> {
+        match self {
+            Metric::Warm => result.timing.as_ref().map(|t| t.median_ns),
+            Metric::Cold => result.cold.as_ref().map(|c| c.median_ns),
+            Metric::Instructions => result.counters.as_ref().map(|c| c.instructions as f64),
+            Metric::Fuel => result.fuel.map(|f| f as f64),
+        }
+    }
+
+    fn scale(self, value: f64) -> (f64, &'static str) {
+        match self {
+            Metric::Warm | Metric::Cold => helper::scale_nanoseconds_value(value),
+            Metric::Instructions | Metric::Fuel => helper::scale_count(value),
+        }
+    }
 }
 
 #[derive(Default)]
 struct AoCBenchmarkPhase {
-    median_for_user: BTreeMap<String, f64>,
+    /// the results per user and input
+    results: BTreeMap<String, BTreeMap<String, PhaseResult>>,
+}
+
+impl AoCBenchmarkPhase {
+    /// the geometric mean of the medians over all benchmarked inputs
+    fn aggregate(&self, user: &str, metric: Metric) -> Option<f64> {
+        let medians: Vec<f64> = self
+            .results
+            .get(user)?
+            .values()
+            .filter(|r| r.status == Status::Ok)
+            .filter_map(|r| metric.value(r))
+            .collect();
+        if medians.is_empty() {
+            return None;
+        }
+        let log_sum: f64 = medians.iter().map(|m| m.ln()).sum();
+        Some((log_sum / medians.len() as f64).exp())
+    }
+
+    /// Whether `user` passed every input that anybody has a `metric` value for. The aggregates
+    /// of other users cover fewer, maybe only the easier inputs, so they are not ranked.
+    fn is_complete(&self, user: &str, metric: Metric) -> bool {
+        let Some(user_results) = self.results.get(user) else {
+            return false;
+        };
+        self.results
+            .values()
+            .flat_map(|inputs| inputs.iter())
+            .filter(|(_, r)| metric.value(r).is_some())
+            .all(|(input, _)| {
+                user_results
+                    .get(input)
+                    .is_some_and(|r| r.status == Status::Ok && metric.value(r).is_some())
+            })
+    }
+
+    fn has_timings(&self, metric: Metric) -> bool {
+        self.results
+            .values()
+            .flat_map(|inputs| inputs.values())
+            .any(|r| metric.value(r).is_some())
+    }
+}
+
+/// The symbol for a failed phase, or for a missing result.
+fn status_symbol(status: Option<Status>) -> &'static str {
+    status.map_or("⁉️", Status::symbol)
+}
+
+/// Renders the cell of `user` for a phase of a day, aggregating over all inputs of the day.
+fn cell(day_benchmarks: &AoCBenchmarkDay, phase: Phase, user: &str, metric: Metric) -> String {
+    let phase_benchmarks = &day_benchmarks.phases[&phase];
+    let user_results = phase_benchmarks.results.get(user);
+    let mut failures = Vec::new();
+    let mut not_implemented = 0;
+    for input in &day_benchmarks.inputs {
+        match user_results.and_then(|r| r.get(input)).map(|r| r.status) {
+            Some(Status::Ok) => {}
+            Some(Status::NotImplemented) => not_implemented += 1,
+            status => failures.push(status_symbol(status)),
+        }
+    }
+    let inputs = day_benchmarks.inputs.len();
+    if inputs > 0 && not_implemented == inputs {
+        return "-".to_string();
+    }
+    let Some(median) = phase_benchmarks.aggregate(user, metric) else {
+        return match failures.first() {
+            Some(symbol) if failures.len() < inputs => {
+                format!("{symbol} {}/{inputs}", failures.len())
+            }
+            Some(symbol) => symbol.to_string(),
+            // verified, but none of the inputs was benchmarked
+            None => "✅".to_string(),
+        };
+    };
+    let fastest = day_benchmarks
+        .min_aggregate(phase, metric)
+        .is_some_and(|min| median < min * 1.05);
+    let maybe_bold = if fastest && day_benchmarks.is_ranked(phase, user, metric) {
+        "**"
+    } else {
+        ""
+    };
+    let (median, unit) = metric.scale(median);
+    let mut time = format!("{}{:.3}{}{}", maybe_bold, median, unit, maybe_bold);
+    if user_results.is_some_and(|r| r.values().any(|r| r.suspected_memoization.is_some())) {
+        time.push_str(" 🧠");
+    }
+    match failures.first() {
+        Some(symbol) => format!("{time} ({symbol} {}/{inputs})", failures.len()),
+        None => time,
+    }
+}
+
+/// A table of the [`Phase::Total`] aggregates of `metric` for every day that has them, with the
+/// rows labeled `label`.
+fn total_table(
+    benchmarks: &AoCBenchmarks,
+    users: &[String],
+    build_failed: &BTreeSet<String>,
+    metric: Metric,
+    label: &str,
+) -> Option<Table> {
+    let days: Vec<_> = benchmarks
+        .days
+        .iter()
+        .filter(|(_, day_benchmarks)| {
+            day_benchmarks
+                .phases
+                .get(&Phase::Total)
+                .is_some_and(|p| p.has_timings(metric))
+        })
+        .collect();
+    if days.is_empty() {
+        return None;
+    }
+    let mut table_builder = Builder::default();
+    // header
+    table_builder.set_header(
+        [
+            vec!["Day", "Phase"],
+            users.iter().map(|s| s.as_str()).collect(),
+        ]
+        .concat(),
+    );
+    for (day, day_benchmarks) in days {
+        let mut row = vec![day.to_string(), label.to_string()];
+        for user in users {
+            if build_failed.contains(user) {
+                row.push("🚧".to_string());
+                continue;
+            }
+            row.push(cell(day_benchmarks, Phase::Total, user, metric));
+        }
+        table_builder.push_record(row);
+    }
+    let mut table = table_builder.build();
+    table
+        .with(Style::markdown())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::right()));
+    Some(table)
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
-    if !args.criterion_dir.is_dir() {
-        return Err(color_eyre::eyre::eyre!("criterion_dir is not a directory"));
+    let mut files = Vec::new();
+    for path in &args.results {
+        if path.is_dir() {
+            let mut entries: Vec<PathBuf> = std::fs::read_dir(path)
+                .with_context(|| format!("cannot read {}", path.display()))?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("jsonl"))
+                .collect();
+            entries.sort();
+            files.extend(entries);
+        } else {
+            files.push(path.clone());
+        }
     }
-    let log = std::fs::read_to_string(&args.logfile)?;
 
     let mut benchmarks = AoCBenchmarks::default();
     let mut users: BTreeSet<String> = BTreeSet::new();
+    // participants that failed to build don't have any benchmarks, but should still show up
+    let mut build_failed: BTreeSet<String> = BTreeSet::new();
+    let mut runs: Vec<RunInfo> = Vec::new();
+    // the number of passed and checked inputs per variant and user
+    let mut robustness: BTreeMap<Variant, BTreeMap<String, (usize, usize)>> = BTreeMap::new();
+    // the (user, input) pairs with results, and those reused from the cache of earlier runs
+    let mut measured: BTreeSet<(String, String)> = BTreeSet::new();
+    let mut cached: BTreeSet<(String, String)> = BTreeSet::new();
+    // the runs whose machine got faster or slower by more than the threshold, and the runs that
+    // cannot be normalized
+    let mut drifted: Vec<(&PathBuf, f64)> = Vec::new();
+    let mut uncalibrated: Vec<&PathBuf> = Vec::new();
+    let mut uncalibrated_cached = 0;
 
-    for entry in std::fs::read_dir(args.criterion_dir)? {
-        let entry = entry?;
-        if !entry.file_type()?.is_dir() {
-            continue;
-        }
-        let entry_file_name = entry.file_name();
-        let testcase_dir = entry_file_name.to_string_lossy();
-        if testcase_dir == "report" {
-            continue;
+    for path in &files {
+        let results = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        let events = results
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("invalid event in {} line {}", path.display(), i + 1))
+            })
+            .collect::<Result<Vec<Event>>>()?;
+        let calibrations: Vec<&Calibration> = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Calibration(calibration) => Some(calibration),
+                _ => None,
+            })
+            .collect();
+        if let Some(drift) = helper::drift(&calibrations) {
+            if drift * 100.0 > args.drift_threshold {
+                drifted.push((path, drift));
+            }
         }
-
-        // our dirs are of the form "username-dayXX-{parse,part1,part2}"
-        if testcase_dir.split("-").count() != 3 {
-            continue;
+        let factor = match (args.normalize, helper::mean_calibration(&calibrations)) {
+            (Some(reference), Some(mean)) => Some(reference / mean),
+            (Some(_), None) => {
+                uncalibrated.push(path);
+                None
+            }
+            (None, _) => None,
+        };
+        for event in events {
+            match event {
+                Event::Run(info) => runs.push(info),
+                Event::BuildFailed { user } => {
+                    users.insert(user.clone());
+                    build_failed.insert(user);
+                }
+                Event::Phase(mut result) => {
+                    // a cached result was measured by an earlier run, at the speed of that run
+                    let factor = match (result.cached, args.normalize) {
+                        (false, _) => factor,
+                        (true, Some(reference)) => match result.calibration_ns {
+                            Some(ns) => Some(reference / ns),
+                            None => {
+                                uncalibrated_cached += 1;
+                                None
+                            }
+                        },
+                        (true, None) => None,
+                    };
+                    if let Some(factor) = factor {
+                        helper::normalize(&mut result, factor);
+                    }
+                    users.insert(result.user.clone());
+                    let input = (result.user.clone(), result.input.clone());
+                    if result.cached {
+                        cached.insert(input.clone());
+                    }
+                    measured.insert(input);
+                    let day = benchmarks.days.entry(result.day).or_default();
+                    day.inputs.insert(result.input.clone());
+                    day.phases
+                        .entry(result.phase)
+                        .or_default()
+                        .results
+                        .entry(result.user.clone())
+                        .or_default()
+                        .insert(result.input.clone(), result);
+                }
+                Event::Robustness(result) => {
+                    users.insert(result.user.clone());
+                    let (passed, checked) = robustness
+                        .entry(result.variant)
+                        .or_default()
+                        .entry(result.user)
+                        .or_default();
+                    *passed += usize::from(result.passed);
+                    *checked += 1;
+                }
+                Event::Calibration(_) => {}
+            }
         }
-
-        let [username, day, phase] = testcase_dir
-            .split("-")
-            .collect::<Vec<_>>()
-            .try_into()
-            .expect("we check its len 3");
-
-        // println!("username: {}, day: {}, phase: {}", username, day, phase);
-        let day = day
-            .strip_prefix("day")
-            .ok_or_else(|| color_eyre::eyre::eyre!("day doesn't start with day"))?
-            .parse::<u8>()?;
-
-        let path = entry.path().join("new/estimates.json");
-        let estimates: serde_json::Value = serde_json::from_reader(
-            std::fs::File::open(&path)
-                .with_context(|| format!("trying to open {}", path.display()))?,
-        )?;
-        let median = estimates["median"]["point_estimate"]
-            .as_f64()
-            .ok_or_else(|| {
-                color_eyre::eyre::eyre!("no median.point_estimate in {}", path.display())
-            })?;
-        users.insert(username.to_string());
-
-        benchmarks
-            .days
-            .entry(day)
-            .or_default()
-            .phases
-            .entry(phase.to_string())
-            .or_default()
-            .median_for_user
-            .insert(username.to_string(), median);
     }
     let users: Vec<String> = users.into_iter().collect();
+    // the legend describes the settings of the first run, the others are checked against it
+    let run = runs.first().cloned().unwrap_or_default();
+    let differing = helper::differing_settings(&runs);
+    let shards: BTreeSet<(usize, usize)> = runs
+        .iter()
+        .filter_map(|r| r.shard.map(|s| (s.count, s.index)))
+        .collect();
 
     let mut table_builder = Builder::default();
     // header
@@ -108,73 +389,66 @@ fn main() -> Result<()> {
     );
 
     for (day, day_benchmarks) in &benchmarks.days {
-        for (phase, phase_benchmarks) in &day_benchmarks.phases {
-            if phase != "Total" {
+        if !day_benchmarks
+            .phases
+            .get(&Phase::Total)
+            .is_some_and(|p| p.has_timings(Metric::Warm))
+        {
+            continue;
+        }
+        let mut row = vec![day.to_string(), Phase::Total.to_string()];
+        for user in &users {
+            if build_failed.contains(user) {
+                row.push("🚧".to_string());
                 continue;
             }
-            let mut row = vec![day.to_string(), phase.to_owned()];
-            let min_median = phase_benchmarks
-                .median_for_user
-                .values()
-                .copied()
-                .min_by(|a, b| a.partial_cmp(b).unwrap())
-                .unwrap_or_default();
-            for user in &users {
-                let mut valid = true;
-                // quick hack, see if any phases are invalid
-                for (phase, phase_benchmarks) in &day_benchmarks.phases {
-                    let median = phase_benchmarks.median_for_user.get(user).copied();
-                    if let Some(_) = median {
-                        continue;
-                    } else {
-                        // check what happened here
-                        if log.contains(&format!("{user}-day{day:02}-{phase}: not implemented")) {
-                            continue;
-                        } else if log.contains(&format!("{user}-day{day:02}-{phase}: error")) {
-                            valid = false;
-                        } else if log.contains(&format!("{user}-day{day:02}-{phase}: timeout")) {
-                            valid = false;
-                        } else if log.contains(&format!("{user}-day{day:02}-{phase}: panicked")) {
-                            valid = false;
-                        } else if log.contains(&format!("{user}-day{day:02}-{phase}: wrong answer"))
-                        {
-                            valid = false;
-                        } else {
-                            valid = false;
-                        }
-                    }
-                }
-                let median = phase_benchmarks.median_for_user.get(user).copied();
-                if let Some(median) = median {
-                    let maybe_bold = if median < min_median * 1.05 { "**" } else { "" };
-                    let (median, unit) = helper::scale_nanoseconds_value(median);
-                    if valid {
-                        row.push(format!("{}{:.3}{}{}", maybe_bold, median, unit, maybe_bold));
-                    } else {
-                        row.push("😔".to_string());
-                    }
-                } else {
-                    // check what happened here
-                    if log.contains(&format!("{user}-day{day:02}-{phase}: not implemented")) {
-                        row.push("-".to_string());
-                    } else if log.contains(&format!("{user}-day{day:02}-{phase}: error")) {
-                        row.push("😔".to_string());
-                    } else if log.contains(&format!("{user}-day{day:02}-{phase}: timeout")) {
-                        row.push("🐌".to_string());
-                    } else if log.contains(&format!("{user}-day{day:02}-{phase}: panicked")) {
-                        row.push("💥".to_string());
-                    } else if log.contains(&format!("{user}-day{day:02}-{phase}: wrong answer")) {
-                        row.push("❌".to_string());
-                    } else {
-                        row.push("⁉️".to_string());
-                    }
-                }
-            }
-            table_builder.push_record(row);
+            // the Total status of an input is an error if any of its phases failed
+            row.push(cell(day_benchmarks, Phase::Total, user, Metric::Warm));
         }
+        table_builder.push_record(row);
     }
     println!("# AoC2024 Benchmark Results");
     println!("");
+    if let Some(&(count, _)) = shards.first() {
+        if shards.len() != count || shards.iter().any(|&(c, _)| c != count) {
+            let included: Vec<String> = shards.iter().map(|(c, i)| format!("{i}/{c}")).collect();
+            println!(
+                "⚠️ Only the shards {} are included, the results are incomplete.",
+                included.join(", ")
+            );
+            println!();
+        }
+    }
+    if !differing.is_empty() {
+        println!(
+            "⚠️ The merged runs differ in {}, their results are not comparable and the legend \
+             only describes the first run.",
+            differing.join(", ")
+        );
+        println!();
+    }
+    for (path, drift) in &drifted {
+        println!(
+            "⚠️ The speed of the machine changed by {:.1}% during {}, its times are less reliable.",
+            drift * 100.0,
+            path.display()
+        );
+        println!();
+    }
+    for path in &uncalibrated {
+        println!(
+            "⚠️ {} has no calibration, its times are not normalized.",
+            path.display()
+        );
+        println!();
+    }
+    if uncalibrated_cached > 0 {
+        println!(
+            "⚠️ {uncalibrated_cached} cached results were measured without a calibration, their \
+             times are not normalized."
+        );
+        println!();
+    }
     println!(
         "{}",
         table_builder
@@ -196,35 +470,16 @@ fn main() -> Result<()> {
     );
     for (day, day_benchmarks) in &benchmarks.days {
         for (phase, phase_benchmarks) in &day_benchmarks.phases {
-            let mut row = vec![day.to_string(), phase.to_owned()];
-            let min_median = phase_benchmarks
-                .median_for_user
-                .values()
-                .copied()
-                .min_by(|a, b| a.partial_cmp(b).unwrap())
-                .unwrap_or_default();
+            if !phase_benchmarks.has_timings(Metric::Warm) {
+                continue;
+            }
+            let mut row = vec![day.to_string(), phase.to_string()];
             for user in &users {
-                let median = phase_benchmarks.median_for_user.get(user).copied();
-                if let Some(median) = median {
-                    let maybe_bold = if median < min_median * 1.05 { "**" } else { "" };
-                    let (median, unit) = helper::scale_nanoseconds_value(median);
-                    row.push(format!("{}{:.3}{}{}", maybe_bold, median, unit, maybe_bold));
-                } else {
-                    // check what happened here
-                    if log.contains(&format!("{user}-day{day:02}-{phase}: not implemented")) {
-                        row.push("-".to_string());
-                    } else if log.contains(&format!("{user}-day{day:02}-{phase}: error")) {
-                        row.push("😔".to_string());
-                    } else if log.contains(&format!("{user}-day{day:02}-{phase}: timeout")) {
-                        row.push("🐌".to_string());
-                    } else if log.contains(&format!("{user}-day{day:02}-{phase}: panicked")) {
-                        row.push("💥".to_string());
-                    } else if log.contains(&format!("{user}-day{day:02}-{phase}: wrong answer")) {
-                        row.push("❌".to_string());
-                    } else {
-                        row.push("⁉️".to_string());
-                    }
+                if build_failed.contains(user) {
+                    row.push("🚧".to_string());
+                    continue;
                 }
+                row.push(cell(day_benchmarks, *phase, user, Metric::Warm));
             }
             table_builder.push_record(row);
         }
@@ -243,16 +498,311 @@ fn main() -> Result<()> {
     println!();
     println!("</details>");
     println!();
-    println!("🐌 - Program timeout (parse: 1sec, part1: 10sec, part2: 30sec)");
+
+    if let Some(table) = total_table(&benchmarks, &users, &build_failed, Metric::Cold, "cold") {
+        println!("## Cold start");
+        println!();
+        println!(
+            "The first run of parse, part1 and part2 in a fresh process, the median over {} runs.",
+            run.cold_runs
+        );
+        println!();
+        println!("{table}");
+        println!();
+    }
+
+    if let Some(table) = total_table(
+        &benchmarks,
+        &users,
+        &build_failed,
+        Metric::Instructions,
+        "instructions",
+    ) {
+        println!("## Instructions");
+        println!();
+        println!(
+            "The instructions retired by parse, part1 and part2 together, counted by the CPU. \
+             Much less noisy than the times, but blind to memory stalls and other threads."
+        );
+        println!();
+        println!("{table}");
+        println!();
+    }
+
+    if let Some(table) = total_table(&benchmarks, &users, &build_failed, Metric::Fuel, "fuel") {
+        println!("## WASM fuel");
+        println!();
+        println!(
+            "The fuel consumed by parse, part1 and part2 of the WASM build in wasmtime, roughly the \
+             number of WASM instructions executed. The same on every machine and in every run."
+        );
+        println!();
+        println!("{table}");
+        println!();
+    }
+
+    if !robustness.is_empty() {
+        let mut table_builder = Builder::default();
+        // header
+        table_builder
+            .set_header([vec!["Variant"], users.iter().map(|s| s.as_str()).collect()].concat());
+        let mut totals: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+        for (variant, variant_results) in &robustness {
+            let mut row = vec![variant.to_string()];
+            for user in &users {
+                if build_failed.contains(user) {
+                    row.push("🚧".to_string());
+                    continue;
+                }
+                match variant_results.get(user) {
+                    Some(&(passed, checked)) => {
+                        let total = totals.entry(user).or_default();
+                        total.0 += passed;
+                        total.1 += checked;
+                        row.push(format!("{passed}/{checked}"));
+                    }
+                    None => row.push("-".to_string()),
+                }
+            }
+            table_builder.push_record(row);
+        }
+        let mut row = vec!["**Score**".to_string()];
+        for user in &users {
+            row.push(match totals.get(user.as_str()) {
+                Some(&(passed, checked)) => {
+                    format!("{:.0}%", 100.0 * passed as f64 / checked as f64)
+                }
+                None if build_failed.contains(user) => "🚧".to_string(),
+                None => "-".to_string(),
+            });
+        }
+        table_builder.push_record(row);
+        println!("## Robustness");
+        println!();
+        println!(
+            "Whether the answers stay the same when the inputs a solution passed are given untrimmed, \
+             with CRLF line endings or with a trailing blank line."
+        );
+        println!();
+        println!(
+            "{}",
+            table_builder
+                .build()
+                .with(Style::markdown())
+                .with(Modify::new(Rows::new(1..)).with(Alignment::right())),
+        );
+        println!();
+    }
+    println!("Times are the geometric mean of the medians over all benchmarked inputs of a day.");
+    println!("Only participants that pass every benchmarked input of a day are ranked.");
+    if let Some(reference) = args.normalize {
+        let (value, unit) = helper::scale_nanoseconds_value(reference);
+        println!(
+            "Times are normalized to the speed of a machine that runs the calibration workload \
+             in {value:.3}{unit}."
+        );
+    }
+    if !cached.is_empty() {
+        println!(
+            "The results of {} of the {} inputs were reused from earlier runs of the same code.",
+            cached.len(),
+            measured.len()
+        );
+    }
+    let mut isolations: Vec<_> = runs.iter().map(|r| &r.isolation).collect();
+    isolations.dedup();
+    for isolation in isolations {
+        if let Some(isolation) = helper::describe_isolation(isolation) {
+            println!("{isolation}");
+        }
+    }
+    // every shard of a run has its own seed
+    let seeds: BTreeSet<u64> = runs.iter().filter_map(|r| r.seed).collect();
+    if matches!(run.order, Order::Shuffled | Order::Interleaved) && !seeds.is_empty() {
+        let seeds: Vec<String> = seeds.iter().map(|seed| seed.to_string()).collect();
+        println!(
+            "The participants and days were measured in {} order (seed {}).",
+            run.order,
+            seeds.join(", ")
+        );
+    }
+    if run.strict {
+        println!(
+            "Every sample was measured in a fresh process, so no state survives between samples."
+        );
+    }
+    println!();
+    println!(
+        "🐌 - Program timeout ({})",
+        helper::describe_timeouts(&run.timeouts)
+    );
     println!("💥 - Program panicked");
     println!("❌ - Program produced invalid result");
+    println!("🎲 - Program gave different answers when run again");
+    println!("🕵️ - Program passes the public input, but fails a held-out one");
+    println!("😔 - Program failed in another phase of the day");
+    println!("(❌ 1/3) - Program failed on 1 of the 3 inputs of the day");
+    println!("✅ - Verified, but not benchmarked");
+    println!("**1.000ms** - Within 5% of the fastest program that passes every input of the day");
+    println!("🧠 - The first run in a fresh process is much slower, answers might be cached across iterations");
+    println!("🚧 - Build failed");
     println!("'-' - Not implemented");
     println!("⁉️ - Unknown error occured");
 
+    // the shards of a run may have run on different machines
+    let mut fingerprints: Vec<(Option<Shard>, &Fingerprint)> = Vec::new();
+    for run in &runs {
+        if !run.fingerprint.rustc.is_empty()
+            && fingerprints.iter().all(|(_, f)| *f != &run.fingerprint)
+        {
+            fingerprints.push((run.shard, &run.fingerprint));
+        }
+    }
+    if !fingerprints.is_empty() {
+        println!();
+        println!("## Environment");
+    }
+    for (shard, fingerprint) in &fingerprints {
+        println!();
+        if let (Some(shard), true) = (shard, fingerprints.len() > 1) {
+            println!("### Shard {shard}");
+            println!();
+        }
+        println!("- {}", fingerprint.rustc);
+        if !fingerprint.rustflags.is_empty() {
+            println!("- RUSTFLAGS: `{}`", fingerprint.rustflags);
+        }
+        println!("- CPU: {} ({} cores)", fingerprint.cpu, fingerprint.cores);
+        println!("- Kernel: {}", fingerprint.kernel);
+        for (user, rev) in &fingerprint.revisions {
+            println!("- {user}: {rev}");
+        }
+    }
+
     Ok(())
 }
 
 mod helper {
+    use super::{Calibration, Isolation, PhaseResult, RunInfo, Timeouts};
+
+    /// The mean calibration time of a run, `None` if it wasn't calibrated.
+    pub fn mean_calibration(calibrations: &[&Calibration]) -> Option<f64> {
+        if calibrations.is_empty() {
+            return None;
+        }
+        Some(calibrations.iter().map(|c| c.median_ns).sum::<f64>() / calibrations.len() as f64)
+    }
+
+    /// How much slower the slowest calibration of a run was than the fastest, e.g. 0.1 for 10%.
+    /// `None` if there are less than two calibrations.
+    pub fn drift(calibrations: &[&Calibration]) -> Option<f64> {
+        if calibrations.len() < 2 {
+            return None;
+        }
+        let times = calibrations.iter().map(|c| c.median_ns);
+        let min = times.clone().fold(f64::INFINITY, f64::min);
+        let max = times.fold(0.0, f64::max);
+        Some(max / min - 1.0)
+    }
+
+    /// Scales the warm and cold times of a result by `factor`.
+    pub fn normalize(result: &mut PhaseResult, factor: f64) {
+        if let Some(timing) = &mut result.timing {
+            timing.median_ns *= factor;
+            timing.median_lower_ns *= factor;
+            timing.median_upper_ns *= factor;
+            timing.mean_ns *= factor;
+        }
+        if let Some(cold) = &mut result.cold {
+            cold.median_ns *= factor;
+            cold.min_ns *= factor;
+        }
+    }
+
+    /// The settings in which the runs differ from the first one, e.g. `["the timeouts"]`.
+    /// Fingerprints are only compared if both runs recorded one.
+    pub fn differing_settings(runs: &[RunInfo]) -> Vec<&'static str> {
+        let Some((first, others)) = runs.split_first() else {
+            return Vec::new();
+        };
+        let differs =
+            |same: fn(&RunInfo, &RunInfo) -> bool| others.iter().any(|run| !same(first, run));
+        [
+            ("the timeouts", differs(|a, b| a.timeouts == b.timeouts)),
+            ("the cold runs", differs(|a, b| a.cold_runs == b.cold_runs)),
+            ("strict mode", differs(|a, b| a.strict == b.strict)),
+            ("the order", differs(|a, b| a.order == b.order)),
+            (
+                "the machine or toolchain",
+                differs(|a, b| {
+                    a.fingerprint.rustc.is_empty()
+                        || b.fingerprint.rustc.is_empty()
+                        || a.fingerprint == b.fingerprint
+                }),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, differs)| differs.then_some(name))
+        .collect()
+    }
+
+    /// e.g. "Benchmarked pinned to CPU 3, at nice -20, with the performance governor, turbo
+    /// boost disabled.", `None` if nothing about the CPU is known
+    pub fn describe_isolation(isolation: &Isolation) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(cpu) = isolation.pinned_cpu {
+            parts.push(format!("pinned to CPU {cpu}"));
+        }
+        if let Some(nice) = isolation.nice {
+            parts.push(format!("at nice {nice}"));
+        }
+        if let Some(governor) = &isolation.governor {
+            parts.push(format!("with the {governor} governor"));
+        }
+        match isolation.turbo {
+            Some(true) => parts.push("turbo boost enabled".to_string()),
+            Some(false) => parts.push("turbo boost disabled".to_string()),
+            None => {}
+        }
+        if parts.is_empty() {
+            return None;
+        }
+        Some(format!("Benchmarked {}.", parts.join(", ")))
+    }
+
+    /// e.g. "parse: 1sec, part1: 10sec, part2: 30sec; day 12 part2: 60sec"
+    pub fn describe_timeouts(timeouts: &Timeouts) -> String {
+        let mut description = format!(
+            "parse: {}sec, part1: {}sec, part2: {}sec",
+            timeouts.parse, timeouts.part1, timeouts.part2
+        );
+        for day in &timeouts.days {
+            for (phase, secs) in [
+                ("parse", day.parse),
+                ("part1", day.part1),
+                ("part2", day.part2),
+            ] {
+                if let Some(secs) = secs {
+                    description.push_str(&format!("; day {} {phase}: {secs}sec", day.day));
+                }
+            }
+        }
+        description
+    }
+
+    /// e.g. (1.5, "M") for 1.5 million
+    pub fn scale_count(count: f64) -> (f64, &'static str) {
+        if count < 1e3 {
+            (count, "")
+        } else if count < 1e6 {
+            (count / 1e3, "k")
+        } else if count < 1e9 {
+            (count / 1e6, "M")
+        } else {
+            (count / 1e9, "G")
+        }
+    }
+
     // made similar to DurationFormatter from criterion
     pub fn scale_nanoseconds_value(ns: f64) -> (f64, &'static str) {
         let (factor, unit) = if ns < 10f64.powi(0) {
@@ -0,0 +1,504 @@
+//! The results of a benchmark run.
+//!
+//! aoc-bench writes one [`Event`] per line as JSON to its results file, aoc-bench-parser reads
+//! them back to render the leaderboard.
+
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// The settings of the run, written once at the start.
+    Run(RunInfo),
+    /// A participant whose solutions failed to build, it has no other results.
+    BuildFailed { user: String },
+    /// The outcome of a single phase of a user's solution on one input.
+    Phase(PhaseResult),
+    /// Whether a user's solution still gives the right answers on a variant of an input.
+    Robustness(RobustnessResult),
+    /// The time of the calibration workload at one point of the run.
+    Calibration(Calibration),
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RunInfo {
+    pub timeouts: Timeouts,
+    /// the number of cold starts measured per benchmarked input, 0 if disabled
+    #[serde(default)]
+    pub cold_runs: usize,
+    /// every sample was measured in a fresh process instead of by criterion
+    #[serde(default)]
+    pub strict: bool,
+    #[serde(default)]
+    pub isolation: Isolation,
+    #[serde(default)]
+    pub fingerprint: Fingerprint,
+    /// the part of the participants and days this run covered, if it was split up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard: Option<Shard>,
+    /// the order the participants and days were measured in
+    #[serde(default)]
+    pub order: Order,
+    /// the seed of a shuffled or interleaved order, to measure in the same order again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+/// The order in which the (participant, day) pairs are measured. Later measurements suffer more
+/// from thermal throttling and drift of the runner, so a fixed order favours the same people.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Order {
+    /// every day of a participant, one participant after another
+    #[default]
+    Sequential,
+    /// all pairs in a random order
+    Shuffled,
+    /// day by day, with the participants of a day measured back to back in a random order
+    Interleaved,
+}
+
+impl FromStr for Order {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sequential" => Ok(Order::Sequential),
+            "shuffled" => Ok(Order::Shuffled),
+            "interleaved" => Ok(Order::Interleaved),
+            _ => Err(format!(
+                "unknown order {s:?}, expected sequential, shuffled or interleaved"
+            )),
+        }
+    }
+}
+
+impl Display for Order {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Order::Sequential => write!(f, "sequential"),
+            Order::Shuffled => write!(f, "shuffled"),
+            Order::Interleaved => write!(f, "interleaved"),
+        }
+    }
+}
+
+/// One of `count` parts of a run, e.g. `2/4`, 1-based. Every (participant, day) pair belongs to
+/// exactly one shard, independent of which participants and days the other shards see.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shard {
+    pub index: usize,
+    pub count: usize,
+}
+
+impl Shard {
+    /// Whether `day` of `user` belongs to this shard.
+    pub fn includes(&self, user: &str, day: u8) -> bool {
+        // FNV-1a, which unlike the std hasher is the same on every machine and toolchain
+        let hash = user
+            .bytes()
+            .chain([0, day])
+            .fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+            });
+        hash % self.count as u64 == (self.index - 1) as u64
+    }
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (index, count) = s
+            .split_once('/')
+            .ok_or_else(|| "expected INDEX/COUNT, e.g. 1/4".to_string())?;
+        let index: usize = index
+            .parse()
+            .map_err(|_| format!("invalid shard index {index:?}"))?;
+        let count: usize = count
+            .parse()
+            .map_err(|_| format!("invalid shard count {count:?}"))?;
+        if !(1..=count).contains(&index) {
+            return Err("the shard index has to be between 1 and the count".to_string());
+        }
+        Ok(Shard { index, count })
+    }
+}
+
+impl Display for Shard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+/// The toolchain, machine and solutions a run used, to tell apart changes of the code from
+/// changes of the runner. Everything that could not be determined is left empty.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Fingerprint {
+    /// the output of `rustc -V` when the harness was built
+    pub rustc: String,
+    /// the RUSTFLAGS the harness was built with
+    pub rustflags: String,
+    /// the CPU model name
+    pub cpu: String,
+    /// the number of CPUs available to the harness
+    pub cores: usize,
+    /// the kernel release, e.g. `6.5.0-1025-azure`
+    pub kernel: String,
+    /// the git revisions of the solutions by participant, missing for local paths
+    pub revisions: BTreeMap<String, String>,
+}
+
+/// How the benchmarking thread was shielded from the rest of the machine.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Isolation {
+    /// the CPU the benchmarks ran on, all other threads were kept off it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_cpu: Option<usize>,
+    /// the raised priority, if it could be raised
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nice: Option<i32>,
+    /// the CPU frequency governor, e.g. `performance`, if it could be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub governor: Option<String>,
+    /// whether turbo boost was enabled, if it could be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turbo: Option<bool>,
+}
+
+/// The time limits of the phases in seconds, with overrides for single days.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Timeouts {
+    pub parse: f64,
+    pub part1: f64,
+    pub part2: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<DayTimeouts>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DayTimeouts {
+    pub day: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part1: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part2: Option<f64>,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Timeouts {
+            parse: 1.0,
+            part1: 10.0,
+            part2: 30.0,
+            days: Vec::new(),
+        }
+    }
+}
+
+impl Timeouts {
+    /// The time limit of a phase of `day` in seconds, `None` for [`Phase::Total`].
+    pub fn get(&self, day: u8, phase: Phase) -> Option<f64> {
+        let day = self.days.iter().find(|d| d.day == day);
+        match phase {
+            Phase::Parse => Some(day.and_then(|d| d.parse).unwrap_or(self.parse)),
+            Phase::Part1 => Some(day.and_then(|d| d.part1).unwrap_or(self.part1)),
+            Phase::Part2 => Some(day.and_then(|d| d.part2).unwrap_or(self.part2)),
+            Phase::Total => None,
+        }
+    }
+
+    /// Sets the time limit of a phase, for all days or only for `day`.
+    pub fn set(&mut self, day: Option<u8>, phase: Phase, secs: f64) {
+        match day {
+            Some(day) => {
+                let day = match self.days.iter().position(|d| d.day == day) {
+                    Some(i) => &mut self.days[i],
+                    None => {
+                        self.days.push(DayTimeouts {
+                            day,
+                            parse: None,
+                            part1: None,
+                            part2: None,
+                        });
+                        self.days.last_mut().unwrap()
+                    }
+                };
+                match phase {
+                    Phase::Parse => day.parse = Some(secs),
+                    Phase::Part1 => day.part1 = Some(secs),
+                    Phase::Part2 => day.part2 = Some(secs),
+                    Phase::Total => {}
+                }
+            }
+            None => match phase {
+                Phase::Parse => self.parse = secs,
+                Phase::Part1 => self.part1 = secs,
+                Phase::Part2 => self.part2 = secs,
+                Phase::Total => {}
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PhaseResult {
+    pub user: String,
+    pub day: u8,
+    /// the name of the input, e.g. `day01` or `day01-franco`
+    pub input: String,
+    pub phase: Phase,
+    pub status: Status,
+    /// only set if the input was benchmarked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+    /// the first execution in a fresh process, only set for [`Phase::Total`] of benchmarked
+    /// inputs when cold runs are enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cold: Option<ColdTiming>,
+    /// the ratio of the cold start to the warm median, only set if it is so high that the
+    /// solution likely caches its answers across iterations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suspected_memoization: Option<f64>,
+    /// the median hardware counters of the phase, only set for benchmarked inputs when the
+    /// counters are enabled and available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counters: Option<HardwareCounters>,
+    /// the fuel consumed by the WASM build of the solution, only set for benchmarked inputs
+    /// when the fuel is measured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuel: Option<u64>,
+    /// the result was reused from an earlier run of the same code on the same input
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// the median time of the calibration workload in the run that measured a cached result, so
+    /// it is normalized with the speed of that run rather than the one that reused it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calibration_ns: Option<f64>,
+    /// details about a failure, e.g. the panic message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RobustnessResult {
+    pub user: String,
+    pub day: u8,
+    pub input: String,
+    pub variant: Variant,
+    pub passed: bool,
+    /// why the variant failed, e.g. the wrong answer or the panic message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A variation of an input file that a checkout or an editor might produce. Solutions normally
+/// get the trimmed input, the variants are passed as they are.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Variant {
+    /// the input as downloaded, ending in a newline
+    Untrimmed,
+    /// the untrimmed input with Windows line endings
+    Crlf,
+    /// the untrimmed input with an additional empty line at the end
+    TrailingBlankLine,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 3] = [
+        Variant::Untrimmed,
+        Variant::Crlf,
+        Variant::TrailingBlankLine,
+    ];
+}
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Variant::Untrimmed => "untrimmed",
+            Variant::Crlf => "CRLF",
+            Variant::TrailingBlankLine => "trailing blank line",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+    /// parsing and solving both parts, only ok if all other phases are
+    Total,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 4] = [Phase::Parse, Phase::Part1, Phase::Part2, Phase::Total];
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Phase::Parse => "parse",
+            Phase::Part1 => "part1",
+            Phase::Part2 => "part2",
+            Phase::Total => "Total",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Ok,
+    Timeout,
+    WrongAnswer,
+    NotImplemented,
+    Panicked,
+    /// a repeated run gave a different answer than the verified one, or failed
+    NonDeterministic,
+    /// passes the public inputs of the day, but not a held-out input. Also used for
+    /// [`Phase::Total`] if one of the other phases is suspicious
+    Suspicious,
+    /// one of the other phases failed, only used for [`Phase::Total`]
+    Error,
+}
+
+impl Status {
+    /// The symbol the leaderboard shows for the status, see its legend.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Status::Ok => "✅",
+            Status::Timeout => "🐌",
+            Status::WrongAnswer => "❌",
+            Status::NotImplemented => "-",
+            Status::Panicked => "💥",
+            Status::NonDeterministic => "🎲",
+            Status::Suspicious => "🕵️",
+            Status::Error => "😔",
+        }
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Status::Ok => "ok",
+            Status::Timeout => "timeout",
+            Status::WrongAnswer => "wrong answer",
+            Status::NotImplemented => "not implemented",
+            Status::Panicked => "panicked",
+            Status::NonDeterministic => "non-deterministic",
+            Status::Suspicious => "suspicious",
+            Status::Error => "error",
+        })
+    }
+}
+
+/// The timings measured by criterion, or in strict mode over one fresh process per sample, in
+/// nanoseconds per iteration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Timing {
+    pub median_ns: f64,
+    /// the 95% confidence interval of the median
+    pub median_lower_ns: f64,
+    pub median_upper_ns: f64,
+    pub mean_ns: f64,
+    /// the number of samples criterion took
+    pub samples: usize,
+    /// the total number of iterations over all samples
+    pub iterations: u64,
+}
+
+/// The time of a cold start, parsing and solving both parts once in a fresh process.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ColdTiming {
+    pub median_ns: f64,
+    pub min_ns: f64,
+    /// the number of processes started
+    pub runs: usize,
+}
+
+/// How long the fixed calibration workload took, which only depends on the speed of the
+/// machine. Comparing it between runs and within a run tells how much the machine differed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Calibration {
+    pub point: CalibrationPoint,
+    pub median_ns: f64,
+    pub min_ns: f64,
+    /// the number of times the workload ran
+    pub runs: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalibrationPoint {
+    Start,
+    Middle,
+    End,
+}
+
+impl Display for CalibrationPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalibrationPoint::Start => write!(f, "start"),
+            CalibrationPoint::Middle => write!(f, "middle"),
+            CalibrationPoint::End => write!(f, "end"),
+        }
+    }
+}
+
+/// The hardware performance counters of a phase, counted in user space only. The counters
+/// other than instructions are not available on every CPU.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HardwareCounters {
+    pub instructions: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycles: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_misses: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_misses: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shard_parses_index_and_count() {
+        let shard: Shard = "2/4".parse().unwrap();
+        assert_eq!(shard, Shard { index: 2, count: 4 });
+        assert_eq!(shard.to_string(), "2/4");
+        assert!("1/1".parse::<Shard>().is_ok());
+    }
+
+    #[test]
+    fn shard_rejects_indices_out_of_range() {
+        for s in ["0/2", "3/2", "1/0", "0/0", "-1/2", "a/2", "1/b", "1", ""] {
+            assert!(s.parse::<Shard>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn every_day_belongs_to_exactly_one_shard() {
+        for count in 1..=5 {
+            for user in ["alice", "bob", "carol"] {
+                for day in 1..=25 {
+                    let shards = (1..=count)
+                        .filter(|&index| Shard { index, count }.includes(user, day))
+                        .count();
+                    assert_eq!(shards, 1, "{user} day {day} with {count} shards");
+                }
+            }
+        }
+    }
+}
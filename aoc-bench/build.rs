@@ -0,0 +1,95 @@
+//! Generates the participant glue from `participants.toml` in the workspace root.
+//!
+//! Cargo resolves dependencies before build scripts run, so the dependencies themselves
+//! have to live in `Cargo.toml`, see `manifest.rs`. The build script only checks that they
+//! are in sync with `participants.toml`, `cargo xtask sync-participants` updates them. The
+//! list of participants is generated into `$OUT_DIR/participants.rs`, which is included by
+//! `main.rs`.
+//!
+//! Without the `static-participants` feature no participant is linked into the harness,
+//! they are built separately as plugins by `aoc-bench build-plugins` instead.
+//!
+//! It also records the rustc version, the RUSTFLAGS and the locked revisions of the
+//! participants for the fingerprint of the results, see `src/fingerprint.rs`.
+
+use std::{fmt::Write, path::PathBuf};
+
+mod manifest;
+#[allow(dead_code)]
+#[path = "src/participants.rs"]
+mod participants;
+
+fn main() {
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let root = manifest_dir
+        .parent()
+        .expect("aoc-bench is part of the workspace");
+    let participants_path = root.join("participants.toml");
+    let cargo_toml_path = manifest_dir.join("Cargo.toml");
+    println!("cargo:rerun-if-changed={}", participants_path.display());
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/participants.rs");
+    println!("cargo:rerun-if-changed=manifest.rs");
+    println!("cargo:rerun-if-changed={}", cargo_toml_path.display());
+    println!(
+        "cargo:rerun-if-changed={}",
+        root.join("Cargo.lock").display()
+    );
+    println!("cargo:rerun-if-env-changed=RUSTFLAGS");
+
+    let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let version = std::process::Command::new(rustc)
+        .arg("-V")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=AOC_BENCH_RUSTC_VERSION={version}");
+    let rustflags = std::env::var("CARGO_ENCODED_RUSTFLAGS").unwrap_or_default();
+    println!(
+        "cargo:rustc-env=AOC_BENCH_RUSTFLAGS={}",
+        rustflags.replace('\x1f', " ")
+    );
+
+    let participants = participants::load(&participants_path).unwrap_or_else(|e| panic!("{e}"));
+
+    // build scripts must not modify the sources, so the blocks are only checked here
+    let cargo_toml = std::fs::read_to_string(&cargo_toml_path).expect("can read Cargo.toml");
+    let outdated = manifest::sync(&cargo_toml, &participants, root, &manifest_dir)
+        .unwrap_or_else(|e| panic!("{e}"));
+    if outdated.is_some() {
+        panic!(
+            "the participant dependencies in {} are out of date with participants.toml, \
+             run `cargo xtask sync-participants` to update them",
+            cargo_toml_path.display()
+        );
+    }
+
+    let mut code = String::from("// @generated by build.rs from participants.toml\n\n");
+    code.push_str("fn participants() -> Vec<Participant> {\n    vec![\n");
+    if std::env::var_os("CARGO_FEATURE_STATIC_PARTICIPANTS").is_some() {
+        for p in &participants {
+            writeln!(
+                code,
+                "        Participant::from_solutions::<{}::{}>({:?}),",
+                p.dependency_key().replace('-', "_"),
+                p.solutions,
+                p.name
+            )
+            .unwrap();
+        }
+    }
+    code.push_str("    ]\n}\n\n");
+    code.push_str("/// The locked git revisions of the participants linked into the harness.\n");
+    code.push_str("fn static_revisions() -> Vec<(&'static str, &'static str)> {\n    vec![\n");
+    if std::env::var_os("CARGO_FEATURE_STATIC_PARTICIPANTS").is_some() {
+        let lockfile = std::fs::read_to_string(root.join("Cargo.lock")).unwrap_or_default();
+        for p in &participants {
+            if let Some(rev) = p.locked_revision(&lockfile) {
+                writeln!(code, "        ({:?}, {rev:?}),", p.name).unwrap();
+            }
+        }
+    }
+    code.push_str("    ]\n}\n");
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    std::fs::write(out_dir.join("participants.rs"), code).expect("can write participants.rs");
+}
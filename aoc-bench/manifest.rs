@@ -0,0 +1,110 @@
+//! The generated blocks of `aoc-bench/Cargo.toml`.
+//!
+//! Cargo resolves dependencies before build scripts run, so the dependencies of the statically
+//! linked participants, and the `static-participants` feature enabling them, have to live in
+//! `Cargo.toml`. They are kept in marked blocks, which `cargo xtask sync-participants`
+//! regenerates from `participants.toml` and the build script checks for being up to date.
+//!
+//! This file is shared between the build script and the xtask, it only depends on the
+//! participants module.
+
+use std::{fmt::Write, path::Path};
+
+use crate::participants::Participant;
+
+/// The names and contents of the generated blocks.
+fn blocks(
+    participants: &[Participant],
+    root: &Path,
+    manifest_dir: &Path,
+) -> [(&'static str, String); 2] {
+    let mut dependencies = String::new();
+    let mut feature = String::from("static-participants = [\n");
+    for p in participants {
+        let key = p.dependency_key();
+        writeln!(
+            dependencies,
+            "{}",
+            p.dependency_line(&key, root, manifest_dir, "optional = true")
+        )
+        .unwrap();
+        writeln!(feature, "    \"dep:{key}\",").unwrap();
+    }
+    feature.push_str("]\n");
+    [("participants", dependencies), ("features", feature)]
+}
+
+/// Replaces the lines between the start and end marker of the block `name` in `manifest`.
+/// Returns `None` if the block is already up to date.
+fn update_block(manifest: &str, name: &str, content: &str) -> Result<Option<String>, String> {
+    let start = format!(
+        "# --- {name}: generated from participants.toml by `cargo xtask sync-participants`, \
+         do not edit ---"
+    );
+    let end = format!("# --- end {name} ---");
+    let (before, rest) = manifest
+        .split_once(&start)
+        .ok_or_else(|| format!("Cargo.toml is missing the line {start:?}"))?;
+    let (current, after) = rest
+        .split_once(&end)
+        .ok_or_else(|| format!("Cargo.toml is missing the line {end:?}"))?;
+    let content = format!("\n{content}");
+    Ok((current != content).then(|| format!("{before}{start}{content}{end}{after}")))
+}
+
+/// The contents of `manifest`, the `Cargo.toml` in `manifest_dir`, with the generated blocks
+/// matching `participants`. Returns `None` if they already match.
+pub fn sync(
+    manifest: &str,
+    participants: &[Participant],
+    root: &Path,
+    manifest_dir: &Path,
+) -> Result<Option<String>, String> {
+    let mut updated: Option<String> = None;
+    for (name, content) in blocks(participants, root, manifest_dir) {
+        let current = updated.as_deref().unwrap_or(manifest);
+        if let Some(manifest) = update_block(current, name, &content)? {
+            updated = Some(manifest);
+        }
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: &str = "# --- deps: generated from participants.toml by `cargo xtask \
+                         sync-participants`, do not edit ---";
+
+    #[test]
+    fn update_block_replaces_the_content_between_the_markers() {
+        let manifest = format!("[dependencies]\n{START}\nold = 1\n# --- end deps ---\nafter\n");
+        let updated = update_block(&manifest, "deps", "new = 2\n").unwrap();
+        assert_eq!(
+            updated.unwrap(),
+            format!("[dependencies]\n{START}\nnew = 2\n# --- end deps ---\nafter\n")
+        );
+    }
+
+    #[test]
+    fn update_block_keeps_an_up_to_date_block() {
+        let manifest = format!("{START}\nnew = 2\n# --- end deps ---\n");
+        assert_eq!(update_block(&manifest, "deps", "new = 2\n").unwrap(), None);
+    }
+
+    #[test]
+    fn update_block_handles_an_empty_block() {
+        let manifest = format!("{START}\n# --- end deps ---\n");
+        assert_eq!(update_block(&manifest, "deps", "").unwrap(), None);
+        let updated = update_block(&manifest, "deps", "a = 1\n").unwrap().unwrap();
+        assert_eq!(updated, format!("{START}\na = 1\n# --- end deps ---\n"));
+    }
+
+    #[test]
+    fn update_block_fails_without_markers() {
+        assert!(update_block("[dependencies]\n", "deps", "").is_err());
+        let manifest = format!("{START}\na = 1\n");
+        assert!(update_block(&manifest, "deps", "").is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! The expected answers of the inputs, stored as salted hashes in a manifest next to the inputs.
+//!
+//! The answers are hashed as `sha256("<salt>:<answer>")` with a random salt per input. Most
+//! answers are small numbers, whose hashes are quick to reverse by trying every number, so the
+//! manifest of encrypted inputs is encrypted with their key as well (`answers.toml.age`). Only
+//! plaintext inputs come with a plaintext `answers.toml`. Answers are normalized by trimming
+//! surrounding whitespace before hashing.
+
+use std::{fmt::Write, path::Path};
+
+use color_eyre::eyre::{eyre, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::inputs::{self, AgeEncryptedDir};
+
+const HEADER: &str = "# Expected answers of the inputs, as sha256(\"<salt>:<answer>\").
+# Use `aoc-bench add-input` to add or update an input, don't edit this by hand.
+# The first inputs of a day are the ones that get benchmarked.
+
+";
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct Answers {
+    #[serde(default, rename = "input")]
+    pub inputs: Vec<ExpectedAnswers>,
+}
+
+/// The expected answers of a single input.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectedAnswers {
+    pub day: u8,
+    /// the name of the input, e.g. `day01` or `day01-franco`
+    pub name: String,
+    salt: String,
+    part1: String,
+    part2: String,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut s, b| {
+        write!(s, "{b:02x}").unwrap();
+        s
+    })
+}
+
+fn hash(salt: &str, answer: &str) -> String {
+    to_hex(&Sha256::digest(format!("{salt}:{}", answer.trim())))
+}
+
+impl ExpectedAnswers {
+    /// Hashes the answers with a fresh salt.
+    pub fn new(day: u8, name: impl Into<String>, part1: &str, part2: &str) -> Self {
+        let salt = to_hex(&rand::random::<[u8; 16]>());
+        ExpectedAnswers {
+            day,
+            name: name.into(),
+            part1: hash(&salt, part1),
+            part2: hash(&salt, part2),
+            salt,
+        }
+    }
+
+    pub fn check_part1(&self, answer: &str) -> bool {
+        hash(&self.salt, answer) == self.part1
+    }
+
+    pub fn check_part2(&self, answer: &str) -> bool {
+        hash(&self.salt, answer) == self.part2
+    }
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read answers {}", path.display()))?;
+        Self::parse(&content, path)
+    }
+
+    /// Loads a manifest encrypted with `key`.
+    pub fn load_encrypted(path: &Path, key: &AgeEncryptedDir) -> Result<Self> {
+        Self::parse(&key.decrypt(path)?, path)
+    }
+
+    /// Parses a manifest that was read from `path`.
+    fn parse(content: &str, path: &Path) -> Result<Self> {
+        let answers: Answers = toml::from_str(content)
+            .with_context(|| format!("cannot parse answers {}", path.display()))?;
+        for a in &answers.inputs {
+            if !(1..=25).contains(&a.day) {
+                return Err(eyre!("input {} has invalid day {}", a.name, a.day));
+            }
+        }
+        Ok(answers)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_toml()?)
+            .with_context(|| format!("cannot write answers {}", path.display()))
+    }
+
+    /// Like [`Answers::save`], but encrypts the manifest with a passphrase.
+    pub fn save_encrypted(&self, path: &Path, passphrase: String) -> Result<()> {
+        std::fs::write(path, inputs::encrypt(&self.to_toml()?, passphrase)?)
+            .with_context(|| format!("cannot write answers {}", path.display()))
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(format!("{HEADER}{}", toml::to_string(self)?))
+    }
+
+    /// Replaces the answers of an existing input, or adds them after the other inputs of the day.
+    pub fn upsert(&mut self, answers: ExpectedAnswers) {
+        if let Some(existing) = self.inputs.iter_mut().find(|a| a.name == answers.name) {
+            *existing = answers;
+        } else {
+            let pos = self
+                .inputs
+                .iter()
+                .position(|a| a.day > answers.day)
+                .unwrap_or(self.inputs.len());
+            self.inputs.insert(pos, answers);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(answers: &Answers) -> Vec<&str> {
+        answers.inputs.iter().map(|a| a.name.as_str()).collect()
+    }
+
+    #[test]
+    fn check_ignores_surrounding_whitespace() {
+        let answers = ExpectedAnswers::new(1, "day01", "42", " abc\n");
+        assert!(answers.check_part1("42\n"));
+        assert!(answers.check_part2("abc"));
+        assert!(!answers.check_part1("43"));
+        assert!(!answers.check_part2("42"));
+    }
+
+    #[test]
+    fn upsert_adds_inputs_after_the_other_inputs_of_their_day() {
+        let mut answers = Answers::default();
+        answers.upsert(ExpectedAnswers::new(3, "day03", "1", "2"));
+        answers.upsert(ExpectedAnswers::new(1, "day01", "1", "2"));
+        answers.upsert(ExpectedAnswers::new(1, "day01-franco", "1", "2"));
+        answers.upsert(ExpectedAnswers::new(5, "day05", "1", "2"));
+        assert_eq!(names(&answers), ["day01", "day01-franco", "day03", "day05"]);
+    }
+
+    #[test]
+    fn upsert_replaces_an_existing_input_in_place() {
+        let mut answers = Answers::default();
+        answers.upsert(ExpectedAnswers::new(1, "day01", "1", "2"));
+        answers.upsert(ExpectedAnswers::new(2, "day02", "1", "2"));
+        answers.upsert(ExpectedAnswers::new(1, "day01", "3", "4"));
+        assert_eq!(names(&answers), ["day01", "day02"]);
+        assert!(answers.inputs[0].check_part1("3"));
+    }
+
+    #[test]
+    fn parse_rejects_days_out_of_range() {
+        let path = Path::new("answers.toml");
+        for day in [1, 25] {
+            let mut answers = Answers::default();
+            answers.upsert(ExpectedAnswers::new(day, "input", "1", "2"));
+            assert!(Answers::parse(&answers.to_toml().unwrap(), path).is_ok());
+        }
+        for day in [0, 26] {
+            let mut answers = Answers::default();
+            answers.upsert(ExpectedAnswers::new(day, "input", "1", "2"));
+            assert!(Answers::parse(&answers.to_toml().unwrap(), path).is_err());
+        }
+    }
+}
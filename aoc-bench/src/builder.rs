@@ -0,0 +1,359 @@
+//! Builds every participant as a separate solutions plugin, so that one participant failing
+//! to compile does not prevent everyone else from being benchmarked.
+//!
+//! For each participant we generate a small `cdylib` crate in `<work_dir>/<name>` that
+//! depends on the participant's solutions crate and exports it via
+//! `aoc_traits::export_solutions!`. On success the library is copied to
+//! `<out_dir>/lib<name>.<ext>`, on failure the compiler diagnostics are written to
+//! `<out_dir>/<name>.build-failed.txt`, which is picked up by [`crate::plugin::load_dir`].
+//!
+//! The git revision the solutions were locked to is written to `<out_dir>/<name>.revision`.
+//!
+//! With `--wasm`, every participant is also built as a `wasm32-wasip1` program in
+//! `<work_dir>/<name>-wasm`, which is copied to `<out_dir>/<name>.wasm` for [`crate::wasm`].
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use color_eyre::eyre::{eyre, Context, Result};
+
+use crate::participants::{self, Participant};
+
+/// The suffix of the files recording the diagnostics of a failed build.
+pub const BUILD_FAILED_SUFFIX: &str = ".build-failed.txt";
+
+/// The suffix of the files recording the git revision a plugin was built from.
+const REVISION_SUFFIX: &str = ".revision";
+
+/// The target the WASM programs are built for, it has to be installed with rustup.
+const WASM_TARGET: &str = "wasm32-wasip1";
+
+/// The line the WASM programs print before each answer, so whatever the solutions print
+/// themselves isn't taken for an answer.
+pub const WASM_ANSWER_MARKER: &str = "--- aoc-bench answer ---";
+
+/// The outcome of building a single participant.
+pub enum BuildOutcome {
+    Built(PathBuf),
+    Failed(String),
+}
+
+fn plugin_file(out_dir: &Path, name: &str) -> PathBuf {
+    out_dir.join(format!(
+        "{}{name}.{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_EXTENSION
+    ))
+}
+
+/// The manifest of a generated crate depending on the solutions of `p`, with `target` being the
+/// `[lib]` or `[[bin]]` section.
+fn manifest(
+    p: &Participant,
+    root: &Path,
+    crate_dir: &Path,
+    kind: &str,
+    target: &str,
+) -> Result<String> {
+    // the plugin has to be built against the same aoc-traits as the harness
+    let aoc_traits = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../aoc-traits"))
+        .canonicalize()
+        .context("cannot find aoc-traits")?;
+    let aoc_traits = toml::Value::String(aoc_traits.to_string_lossy().into_owned());
+    Ok(format!(
+        r#"# @generated by aoc-bench build-plugins
+[package]
+name = "aoc-{kind}-{name}"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+{target}
+
+[dependencies]
+aoc-traits = {{ path = {aoc_traits} }}
+{solutions}
+
+# the solutions depend on aoc-traits via git, make sure they use the same version as the harness
+[patch."https://github.com/dkales/adventofcode2024-leaderboard"]
+aoc-traits = {{ path = {aoc_traits} }}
+
+[workspace]
+"#,
+        name = p.name.to_lowercase(),
+        solutions = p.dependency_line("solutions", root, crate_dir, ""),
+    ))
+}
+
+fn write_crate(p: &Participant, root: &Path, crate_dir: &Path) -> Result<()> {
+    let manifest = manifest(
+        p,
+        root,
+        crate_dir,
+        "plugin",
+        "[lib]\ncrate-type = [\"cdylib\"]",
+    )?;
+    let lib = format!(
+        "// @generated by aoc-bench build-plugins\naoc_traits::export_solutions!(solutions::{});\n",
+        p.solutions
+    );
+    std::fs::create_dir_all(crate_dir.join("src"))?;
+    std::fs::write(crate_dir.join("Cargo.toml"), manifest)?;
+    std::fs::write(crate_dir.join("src/lib.rs"), lib)?;
+    Ok(())
+}
+
+/// The WASM program, called with the day as its only argument and the input on stdin. It reports
+/// the start and end of every phase to the harness, which reads the fuel consumed in between.
+/// The answers are printed at the end, each after a line with the [`WASM_ANSWER_MARKER`].
+const WASM_MAIN: &str = r#"// @generated by aoc-bench build-plugins
+use std::io::Read;
+
+use aoc_traits::ffi::{FfiBuffer, FfiSolutions, FfiStatus};
+
+#[link(wasm_import_module = "aoc_bench")]
+extern "C" {
+    fn phase_start();
+    fn phase_end(phase: u32);
+}
+
+fn finish(solutions: &FfiSolutions, status: FfiStatus, out: FfiBuffer) -> String {
+    let s = unsafe { out.as_str() }.to_string();
+    unsafe { (solutions.free_buffer)(out) };
+    if status == FfiStatus::Panic {
+        eprintln!("{s}");
+        std::process::exit(101);
+    }
+    s
+}
+
+fn main() {
+    let day: usize = std::env::args()
+        .nth(1)
+        .and_then(|day| day.parse().ok())
+        .expect("usage: <day> < input");
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input).unwrap();
+    let input = input.trim();
+    let solutions = aoc_traits::ffi::solutions::<SOLUTIONS>();
+    let day = solutions.days[day - 1];
+    let mut parsed = std::ptr::null_mut();
+    let mut out = FfiBuffer::empty();
+    unsafe { phase_start() };
+    let status = unsafe { (day.parse)(input.as_ptr(), input.len(), &mut parsed, &mut out) };
+    unsafe { phase_end(0) };
+    finish(&solutions, status, out);
+    let mut out = FfiBuffer::empty();
+    unsafe { phase_start() };
+    let status = unsafe { (day.part1)(parsed, &mut out) };
+    unsafe { phase_end(1) };
+    let part1 = finish(&solutions, status, out);
+    let mut out = FfiBuffer::empty();
+    unsafe { phase_start() };
+    let status = unsafe { (day.part2)(parsed, &mut out) };
+    unsafe { phase_end(2) };
+    let part2 = finish(&solutions, status, out);
+    print!("\nANSWER_MARKER\n{part1}\nANSWER_MARKER\n{part2}\n");
+}
+"#;
+
+fn write_wasm_crate(p: &Participant, root: &Path, crate_dir: &Path) -> Result<()> {
+    let manifest = manifest(
+        p,
+        root,
+        crate_dir,
+        "wasm",
+        "[[bin]]\nname = \"solutions\"\npath = \"src/main.rs\"",
+    )?;
+    let main = WASM_MAIN
+        .replace("SOLUTIONS", &format!("solutions::{}", p.solutions))
+        .replace("ANSWER_MARKER", WASM_ANSWER_MARKER);
+    std::fs::create_dir_all(crate_dir.join("src"))?;
+    std::fs::write(crate_dir.join("Cargo.toml"), manifest)?;
+    std::fs::write(crate_dir.join("src/main.rs"), main)?;
+    Ok(())
+}
+
+/// Runs cargo on the generated crate, returning the built library or the diagnostics. With
+/// `wasm` set, it is built for WASI and the program is returned instead.
+fn cargo_build(crate_dir: &Path, target_dir: &Path, wasm: bool) -> Result<BuildOutcome> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let (kind, suffix) = if wasm {
+        ("bin", ".wasm")
+    } else {
+        ("cdylib", std::env::consts::DLL_SUFFIX)
+    };
+    let mut command = Command::new(cargo);
+    command.args(["build", "--release", "--message-format=json"]);
+    if wasm {
+        command.args(["--target", WASM_TARGET]);
+    }
+    let output = command
+        .arg("--manifest-path")
+        .arg(crate_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(target_dir)
+        .stdin(Stdio::null())
+        .output()
+        .context("cannot run cargo")?;
+
+    let mut library = None;
+    let mut diagnostics = String::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Ok(message) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        match message["reason"].as_str() {
+            Some("compiler-message") => {
+                if message["message"]["level"] == "error" {
+                    if let Some(rendered) = message["message"]["rendered"].as_str() {
+                        diagnostics.push_str(rendered);
+                    }
+                }
+            }
+            Some("compiler-artifact") => {
+                let is_kind = message["target"]["kind"]
+                    .as_array()
+                    .is_some_and(|kinds| kinds.iter().any(|k| k == kind));
+                if is_kind {
+                    library = message["filenames"]
+                        .as_array()
+                        .and_then(|f| {
+                            f.iter()
+                                .filter_map(|f| f.as_str())
+                                .find(|f| f.ends_with(suffix))
+                        })
+                        .map(PathBuf::from);
+                }
+            }
+            _ => {}
+        }
+    }
+    match library {
+        Some(library) if output.status.success() => Ok(BuildOutcome::Built(library)),
+        _ => {
+            // failures outside of rustc, e.g. fetching the sources, only show up on stderr
+            if diagnostics.is_empty() {
+                diagnostics = String::from_utf8_lossy(&output.stderr).into_owned();
+            }
+            Ok(BuildOutcome::Failed(diagnostics))
+        }
+    }
+}
+
+/// Builds all participants listed in `participants_file` into `out_dir`, and with `wasm` set
+/// also as WASM programs. The outcomes of the WASM builds are named `<name>.wasm`.
+pub fn build_all(
+    participants_file: &Path,
+    work_dir: &Path,
+    out_dir: &Path,
+    wasm: bool,
+) -> Result<Vec<(String, BuildOutcome)>> {
+    let participants = participants::load(participants_file).map_err(|e| eyre!(e))?;
+    let root = participants_file
+        .parent()
+        .ok_or_else(|| eyre!("invalid participants file"))?
+        .canonicalize()?;
+    std::fs::create_dir_all(work_dir)?;
+    std::fs::create_dir_all(out_dir)?;
+    let work_dir = work_dir.canonicalize()?;
+    let target_dir = work_dir.join("target");
+
+    let mut outcomes = Vec::new();
+    for p in &participants {
+        println!("Building participant {}", p.name);
+        let plugin = plugin_file(out_dir, &p.name);
+        let failed = out_dir.join(format!("{}{BUILD_FAILED_SUFFIX}", p.name));
+        let program = out_dir.join(format!("{}.wasm", p.name));
+        let revision = out_dir.join(format!("{}{REVISION_SUFFIX}", p.name));
+        // never pick up stale results of a previous build
+        for stale in [&plugin, &failed, &program, &revision] {
+            if stale.exists() {
+                std::fs::remove_file(stale)?;
+            }
+        }
+
+        let crate_dir = work_dir.join(&p.name);
+        write_crate(p, &root, &crate_dir)?;
+        let outcome = match cargo_build(&crate_dir, &target_dir, false)? {
+            BuildOutcome::Built(library) => {
+                std::fs::copy(&library, &plugin).with_context(|| {
+                    format!("cannot copy {} to {}", library.display(), plugin.display())
+                })?;
+                let lockfile = std::fs::read_to_string(crate_dir.join("Cargo.lock"))?;
+                if let Some(rev) = p.locked_revision(&lockfile) {
+                    std::fs::write(&revision, rev)?;
+                }
+                BuildOutcome::Built(plugin)
+            }
+            BuildOutcome::Failed(diagnostics) => {
+                std::fs::write(&failed, &diagnostics)?;
+                BuildOutcome::Failed(diagnostics)
+            }
+        };
+        match &outcome {
+            BuildOutcome::Built(_) => println!("{}-build: ok", p.name),
+            BuildOutcome::Failed(_) => println!("{}-build: build failed", p.name),
+        }
+        outcomes.push((p.name.clone(), outcome));
+
+        if wasm {
+            // a failed WASM build only leaves the participant out of the fuel table
+            let crate_dir = work_dir.join(format!("{}-wasm", p.name));
+            write_wasm_crate(p, &root, &crate_dir)?;
+            let outcome = match cargo_build(&crate_dir, &target_dir, true)? {
+                BuildOutcome::Built(wasm) => {
+                    std::fs::copy(&wasm, &program).with_context(|| {
+                        format!("cannot copy {} to {}", wasm.display(), program.display())
+                    })?;
+                    println!("{}-wasm-build: ok", p.name);
+                    BuildOutcome::Built(program)
+                }
+                BuildOutcome::Failed(diagnostics) => {
+                    println!("{}-wasm-build: build failed", p.name);
+                    BuildOutcome::Failed(diagnostics)
+                }
+            };
+            outcomes.push((format!("{}.wasm", p.name), outcome));
+        }
+    }
+    Ok(outcomes)
+}
+
+/// The participants whose build failed, according to the records in `out_dir`.
+pub fn failed_builds(out_dir: &Path) -> Result<Vec<String>> {
+    let mut failed: Vec<String> = std::fs::read_dir(out_dir)
+        .with_context(|| format!("cannot read plugin dir {}", out_dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let name = name.to_str()?.strip_suffix(BUILD_FAILED_SUFFIX)?;
+            Some(name.to_string())
+        })
+        .collect();
+    failed.sort();
+    Ok(failed)
+}
+
+/// The git revisions the plugins in `out_dir` were built from, by participant.
+pub fn revisions(out_dir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut revisions = BTreeMap::new();
+    for entry in std::fs::read_dir(out_dir)
+        .with_context(|| format!("cannot read plugin dir {}", out_dir.display()))?
+    {
+        let path = entry?.path();
+        let Some(name) = path
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_suffix(REVISION_SUFFIX))
+        else {
+            continue;
+        };
+        let rev = std::fs::read_to_string(&path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        revisions.insert(name.to_string(), rev.trim().to_string());
+    }
+    Ok(revisions)
+}
@@ -0,0 +1,170 @@
+//! Reuses the results of inputs whose solution, input and harness didn't change since an earlier
+//! run, so the hourly runs only measure what somebody pushed.
+//!
+//! The results of an input are stored as `<dir>/<user>/<key>.jsonl`, where the key is a hash of
+//! the git revision of the solutions, the day, the name and content of the input, whether it is
+//! benchmarked, and the harness. The harness covers a hash of the aoc-bench binary, the toolchain,
+//! the RUSTFLAGS, the CPU, and every setting that changes the results. Participants without a git
+//! revision are never cached.
+//!
+//! The harness changes with every build, so a run deletes the entries it didn't use at the end,
+//! see [`Cache::prune`].
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use aoc_bench_results::{Event, Fingerprint, Status};
+use color_eyre::eyre::{Context, Result};
+use sha2::{Digest, Sha256};
+
+use crate::{BenchOptions, TestCase};
+
+pub struct Cache {
+    dir: PathBuf,
+    /// the git revisions of the solutions by participant
+    revisions: BTreeMap<String, String>,
+    harness: String,
+    /// the entries loaded or stored by this run
+    used: RefCell<BTreeSet<PathBuf>>,
+}
+
+impl Cache {
+    pub fn new(dir: &Path, fingerprint: &Fingerprint, options: &BenchOptions) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("cannot create cache dir {}", dir.display()))?;
+        // the version doesn't change with every change of the harness, its binary does
+        let exe = std::env::current_exe().context("cannot find the aoc-bench binary")?;
+        let binary = std::fs::read(&exe)
+            .with_context(|| format!("cannot read the aoc-bench binary {}", exe.display()))?;
+        let criterion = &options.criterion;
+        let harness = format!(
+            "aoc-bench {:x}; rustc {}; rustflags {}; cpu {} x{}; timeouts {:?}; \
+             criterion {} {} {}; cold {} {}; strict {}; determinism {} {}; robustness {}; \
+             counters {}; wasm {}",
+            Sha256::digest(binary),
+            fingerprint.rustc,
+            fingerprint.rustflags,
+            fingerprint.cpu,
+            fingerprint.cores,
+            options.timeouts,
+            criterion.warm_up,
+            criterion.measurement_time,
+            criterion.sample_size,
+            options.cold_runs,
+            options.memoization_ratio,
+            options.strict,
+            options.determinism_runs,
+            options.determinism_processes,
+            options.robustness,
+            options.counters,
+            options.wasm.is_some(),
+        );
+        Ok(Cache {
+            dir: dir.to_path_buf(),
+            revisions: fingerprint.revisions.clone(),
+            harness,
+            used: RefCell::default(),
+        })
+    }
+
+    fn path(&self, user: &str, case: &TestCase, benchmark: bool) -> Option<PathBuf> {
+        let revision = self.revisions.get(user)?;
+        let key = Sha256::new()
+            .chain_update(revision)
+            .chain_update([case.day])
+            .chain_update(case.name)
+            .chain_update(Sha256::digest(case.input))
+            .chain_update([u8::from(benchmark)])
+            .chain_update(&self.harness)
+            .finalize();
+        let key = format!("{key:x}");
+        Some(self.dir.join(user).join(format!("{}.jsonl", &key[..32])))
+    }
+
+    /// The results of an earlier run, if there is one.
+    pub fn load(&self, user: &str, case: &TestCase, benchmark: bool) -> Result<Option<Vec<Event>>> {
+        let Some(path) = self.path(user, case, benchmark) else {
+            return Ok(None);
+        };
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Ok(None);
+        };
+        let events = content
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<Event>, _>>()
+            .with_context(|| format!("invalid cache entry {}", path.display()))?;
+        self.used.borrow_mut().insert(path);
+        Ok(Some(events))
+    }
+
+    /// Stores the results of an input together with `calibration_ns`, the latest calibration
+    /// time of this run. Timeouts can be caused by a slow runner, so results with a timeout are
+    /// measured again next time.
+    pub fn store(
+        &self,
+        user: &str,
+        case: &TestCase,
+        benchmark: bool,
+        events: &[Event],
+        calibration_ns: Option<f64>,
+    ) -> Result<()> {
+        let Some(path) = self.path(user, case, benchmark) else {
+            return Ok(());
+        };
+        let timed_out = events
+            .iter()
+            .any(|e| matches!(e, Event::Phase(result) if result.status == Status::Timeout));
+        if timed_out {
+            return Ok(());
+        }
+        let mut content = String::new();
+        for event in events {
+            let mut event = event.clone();
+            if let Event::Phase(result) = &mut event {
+                result.calibration_ns = calibration_ns;
+            }
+            content.push_str(&serde_json::to_string(&event)?);
+            content.push('\n');
+        }
+        std::fs::create_dir_all(path.parent().expect("the path is in the cache dir"))?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("cannot write {}", path.display()))?;
+        self.used.borrow_mut().insert(path);
+        Ok(())
+    }
+
+    /// Deletes the entries this run neither loaded nor stored, together with the directories of
+    /// participants that are left without any. Their solutions, inputs or the harness changed,
+    /// so no later run can use them either, and the cache would grow with every run otherwise.
+    pub fn prune(&self) -> Result<()> {
+        let used = self.used.borrow();
+        let mut removed = 0;
+        for user_dir in std::fs::read_dir(&self.dir)
+            .with_context(|| format!("cannot read cache dir {}", self.dir.display()))?
+        {
+            let user_dir = user_dir?.path();
+            if !user_dir.is_dir() {
+                continue;
+            }
+            for entry in std::fs::read_dir(&user_dir)? {
+                let path = entry?.path();
+                if !used.contains(&path) {
+                    std::fs::remove_file(&path)
+                        .with_context(|| format!("cannot remove {}", path.display()))?;
+                    removed += 1;
+                }
+            }
+            if std::fs::read_dir(&user_dir)?.next().is_none() {
+                std::fs::remove_dir(&user_dir)?;
+            }
+        }
+        if removed > 0 {
+            println!("Removed {removed} unused results from the cache");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+//! A fixed workload measured at the start, in the middle and at the end of a run. Its time only
+//! depends on the machine, so the parser can put runs on machines of different speed on the same
+//! scale and notice when the machine got slower or faster during a run.
+
+use std::time::{Duration, Instant};
+
+use aoc_bench_results::{Calibration, CalibrationPoint, Event};
+use color_eyre::eyre::Result;
+use criterion::black_box;
+
+use crate::results::ResultsWriter;
+
+/// The size of the sieve and of the sorted vector, some tens of milliseconds of work.
+const SIZE: usize = 1 << 20;
+
+/// A mix of what AoC solutions spend their time on: a sieve walking memory with strides, sorting
+/// pseudo-random numbers with unpredictable branches, and integer arithmetic.
+fn workload(size: usize) -> u64 {
+    let mut composite = vec![false; size];
+    let mut primes = 0u64;
+    for n in 2..size {
+        if !composite[n] {
+            primes += 1;
+            for multiple in (n * n..size).step_by(n) {
+                composite[multiple] = true;
+            }
+        }
+    }
+    // xorshift, so the numbers are the same on every machine
+    let mut state = 0x2545f4914f6cdd1du64;
+    let mut numbers: Vec<u64> = (0..size)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % 1_000_000
+        })
+        .collect();
+    numbers.sort_unstable();
+    let checksum = numbers
+        .iter()
+        .step_by(1024)
+        .fold(0u64, |acc, n| acc.wrapping_mul(31).wrapping_add(*n));
+    primes ^ checksum
+}
+
+/// Measures the workload at `point` of the run and writes the result, unless `runs` is 0.
+/// Returns the median time in nanoseconds.
+pub fn run(
+    point: CalibrationPoint,
+    runs: usize,
+    results: &mut ResultsWriter,
+) -> Result<Option<f64>> {
+    if runs == 0 {
+        return Ok(None);
+    }
+    let calibration = measure(point, runs);
+    println!(
+        "Calibration at the {point}: {:?}",
+        Duration::from_nanos(calibration.median_ns as u64)
+    );
+    let median_ns = calibration.median_ns;
+    results.write(&Event::Calibration(calibration))?;
+    Ok(Some(median_ns))
+}
+
+/// Runs the workload `runs` times on the calling thread, which should be the benchmarking one.
+fn measure(point: CalibrationPoint, runs: usize) -> Calibration {
+    // the first run warms up the allocator and the caches
+    black_box(workload(black_box(SIZE)));
+    let mut times: Vec<f64> = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(workload(black_box(SIZE)));
+            start.elapsed().as_nanos() as f64
+        })
+        .collect();
+    times.sort_by(f64::total_cmp);
+    Calibration {
+        point,
+        median_ns: times[times.len() / 2],
+        min_ns: times[0],
+        runs,
+    }
+}
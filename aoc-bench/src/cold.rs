@@ -0,0 +1,212 @@
+//! Cold-start measurements: the first execution of a day in a fresh process, without warm
+//! caches, a trained branch predictor or lazily initialized state of earlier iterations.
+//!
+//! The harness runs itself again with the hidden `run-once` subcommand, passes the input on
+//! stdin and reads a [`RunOutput`] from stdout. The determinism checks use the same processes
+//! to compare answers across process boundaries, and the strict mode takes every sample of a
+//! benchmark in one of them.
+
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    path::Path,
+    process::{Child, Command, ExitStatus, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use aoc_bench_results::{ColdTiming, Phase, Timing};
+use color_eyre::eyre::{eyre, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::solver::Participant;
+
+/// What a `run-once` process prints as the last line of its output.
+#[derive(Serialize, Deserialize)]
+pub struct RunOutput {
+    /// the time of parse, part1 and part2 together
+    pub elapsed_ns: u64,
+    /// the times of parse, part1 and part2
+    pub phases_ns: [u64; 3],
+    pub part1: String,
+    pub part2: String,
+}
+
+/// The child side: runs parse, part1 and part2 of `day` once on the input from stdin and
+/// prints the answers and how long it took.
+pub fn run(participant: &Participant, day: u8) -> Result<()> {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let (phases, part1, part2) = participant.day(day).run_once(&input);
+    let phases_ns = phases.map(|d| d.as_nanos() as u64);
+    let output = RunOutput {
+        elapsed_ns: phases_ns.iter().sum(),
+        phases_ns,
+        part1,
+        part2,
+    };
+    println!("{}", serde_json::to_string(&output)?);
+    Ok(())
+}
+
+/// How a run-once process ended.
+pub enum ProcessOutcome {
+    Finished(RunOutput),
+    /// the process exited unsuccessfully, e.g. after a panic or a segfault
+    Crashed {
+        status: ExitStatus,
+        stderr: String,
+    },
+    TimedOut,
+}
+
+impl ProcessOutcome {
+    /// The output of a successful run, or an error describing the failure.
+    pub fn into_result(self) -> Result<RunOutput> {
+        match self {
+            ProcessOutcome::Finished(output) => Ok(output),
+            ProcessOutcome::Crashed { status, .. } => {
+                Err(eyre!("the process failed with {status}"))
+            }
+            ProcessOutcome::TimedOut => Err(eyre!("the process timed out")),
+        }
+    }
+}
+
+/// Kills the child when dropped, so a failed measurement doesn't leave it running.
+struct KillOnDrop(Child);
+
+impl Drop for KillOnDrop {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Starts a fresh process running `day` of `user` once, killing it after `timeout`.
+pub fn run_process(
+    plugin_dir: Option<&Path>,
+    user: &str,
+    day: u8,
+    input: &str,
+    timeout: Duration,
+) -> Result<ProcessOutcome> {
+    let mut command = Command::new(std::env::current_exe()?);
+    if let Some(plugin_dir) = plugin_dir {
+        command.arg("--plugin-dir").arg(plugin_dir);
+    }
+    let child = command
+        .args(["run-once", "--user", user, "--day", &day.to_string()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("cannot start the run-once process")?;
+    let mut child = KillOnDrop(child);
+    let mut stdin = child.0.stdin.take().unwrap();
+    let stdout = child.0.stdout.take().unwrap();
+    let stderr = child.0.stderr.take().unwrap();
+    let input = input.to_string();
+    // write and read from other threads, so no pipe can fill up and block the child
+    thread::spawn(move || {
+        let _ = stdin.write_all(input.as_bytes());
+    });
+    let read = |mut pipe: Box<dyn Read + Send>| {
+        thread::spawn(move || {
+            let mut output = Vec::new();
+            let _ = pipe.read_to_end(&mut output);
+            String::from_utf8_lossy(&output).into_owned()
+        })
+    };
+    let stdout = read(Box::new(stdout));
+    let stderr = read(Box::new(stderr));
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.0.try_wait()? {
+            break status;
+        }
+        if start.elapsed() > timeout {
+            return Ok(ProcessOutcome::TimedOut);
+        }
+        thread::sleep(Duration::from_millis(1));
+    };
+    if !status.success() {
+        return Ok(ProcessOutcome::Crashed {
+            status,
+            stderr: stderr.join().unwrap(),
+        });
+    }
+    let output = stdout.join().unwrap();
+    // the solution might print as well, the result is the last line
+    serde_json::from_str(output.lines().last().unwrap_or_default())
+        .map(ProcessOutcome::Finished)
+        .map_err(|_| eyre!("unexpected output {output:?}"))
+}
+
+/// Measures `runs` cold starts of `day` of `user` and summarizes them.
+pub fn measure_runs(
+    plugin_dir: Option<&Path>,
+    user: &str,
+    day: u8,
+    input: &str,
+    runs: usize,
+    timeout: Duration,
+) -> Result<ColdTiming> {
+    let mut times = (0..runs)
+        .map(|_| {
+            let output = run_process(plugin_dir, user, day, input, timeout)?.into_result()?;
+            Ok(Duration::from_nanos(output.elapsed_ns))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    times.sort();
+    let ns = |d: Duration| d.as_nanos() as f64;
+    Ok(ColdTiming {
+        median_ns: (ns(times[(runs - 1) / 2]) + ns(times[runs / 2])) / 2.0,
+        min_ns: ns(times[0]),
+        runs,
+    })
+}
+
+/// Summarizes the times of a phase in nanoseconds, one sample per process.
+fn summarize(mut samples: Vec<f64>) -> Timing {
+    samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let n = samples.len();
+    // the ranks of the distribution-free 95% confidence interval of the median
+    let spread = 0.98 * (n as f64).sqrt();
+    let lower = ((n as f64 / 2.0 - spread).floor() as usize).min(n - 1);
+    let upper = ((n as f64 / 2.0 + spread).ceil() as usize).min(n - 1);
+    Timing {
+        median_ns: (samples[(n - 1) / 2] + samples[n / 2]) / 2.0,
+        median_lower_ns: samples[lower],
+        median_upper_ns: samples[upper],
+        mean_ns: samples.iter().sum::<f64>() / n as f64,
+        samples: n,
+        iterations: n as u64,
+    }
+}
+
+/// Measures every sample of `day` of `user` in a fresh process, so no state like a cached
+/// answer can survive from one sample to the next. Returns the timings of every phase.
+pub fn measure_strict(
+    plugin_dir: Option<&Path>,
+    user: &str,
+    day: u8,
+    input: &str,
+    samples: usize,
+    timeout: Duration,
+) -> Result<BTreeMap<Phase, Timing>> {
+    let mut times: BTreeMap<Phase, Vec<f64>> = BTreeMap::new();
+    for _ in 0..samples {
+        let output = run_process(plugin_dir, user, day, input, timeout)?.into_result()?;
+        let phases = [Phase::Parse, Phase::Part1, Phase::Part2];
+        for (phase, ns) in phases.into_iter().zip(output.phases_ns) {
+            times.entry(phase).or_default().push(ns as f64);
+        }
+        let total = times.entry(Phase::Total).or_default();
+        total.push(output.elapsed_ns as f64);
+    }
+    Ok(times
+        .into_iter()
+        .map(|(phase, samples)| (phase, summarize(samples)))
+        .collect())
+}
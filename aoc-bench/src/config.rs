@@ -0,0 +1,322 @@
+//! The optional config file of aoc-bench. Settings given on the command line take precedence.
+//!
+//! ```toml
+//! [timeouts]
+//! parse = 1
+//! part1 = 10
+//! part2 = 30
+//!
+//! # overrides for single days
+//! [[timeouts.days]]
+//! day = 12
+//! part2 = 60
+//!
+//! [criterion]
+//! profile = "quick"
+//! # overrides for the settings of the profile
+//! warm_up = 0.5
+//! measurement_time = 1
+//! sample_size = 20
+//! ```
+
+use std::{path::Path, str::FromStr, time::Duration};
+
+use aoc_bench_results::{Phase, Timeouts};
+use clap::ValueEnum;
+use color_eyre::eyre::{eyre, Context, Result};
+use serde::Deserialize;
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub timeouts: Timeouts,
+    pub criterion: CriterionArgs,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read config {}", path.display()))?;
+        let config: Config = toml::from_str(&content)
+            .with_context(|| format!("cannot parse config {}", path.display()))?;
+        let timeouts = &config.timeouts;
+        if let Some(d) = timeouts.days.iter().find(|d| !(1..=25).contains(&d.day)) {
+            return Err(eyre!(
+                "invalid day {} in the timeouts of {}",
+                d.day,
+                path.display()
+            ));
+        }
+        let days = timeouts
+            .days
+            .iter()
+            .flat_map(|d| [d.parse, d.part1, d.part2]);
+        if ![timeouts.parse, timeouts.part1, timeouts.part2]
+            .into_iter()
+            .chain(days.flatten())
+            .all(is_positive_secs)
+        {
+            return Err(eyre!(
+                "the timeouts in {} have to be positive",
+                path.display()
+            ));
+        }
+        Ok(config)
+    }
+}
+
+/// Whether a time in seconds is positive and finite, so it can be converted to a [`Duration`].
+fn is_positive_secs(secs: f64) -> bool {
+    secs > 0.0 && secs.is_finite()
+}
+
+/// A `--timeout` argument of the form `[DAY:]PHASE=SECONDS`, where `PHASE` is `parse`,
+/// `part1`, `part2` or `all`.
+#[derive(Clone)]
+pub struct TimeoutArg {
+    day: Option<u8>,
+    phases: Vec<Phase>,
+    secs: f64,
+}
+
+impl FromStr for TimeoutArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (target, secs) = s
+            .split_once('=')
+            .ok_or_else(|| "expected [DAY:]PHASE=SECONDS".to_string())?;
+        let secs: f64 = secs
+            .parse()
+            .map_err(|_| format!("invalid number of seconds {secs:?}"))?;
+        if !is_positive_secs(secs) {
+            return Err("the timeout has to be positive".to_string());
+        }
+        let (day, phase) = match target.split_once(':') {
+            Some((day, phase)) => {
+                let day: u8 = day.parse().map_err(|_| format!("invalid day {day:?}"))?;
+                if !(1..=25).contains(&day) {
+                    return Err("the day has to be between 1 and 25".to_string());
+                }
+                (Some(day), phase)
+            }
+            None => (None, target),
+        };
+        let phases = match phase {
+            "parse" => vec![Phase::Parse],
+            "part1" => vec![Phase::Part1],
+            "part2" => vec![Phase::Part2],
+            "all" => vec![Phase::Parse, Phase::Part1, Phase::Part2],
+            _ => {
+                return Err(format!(
+                    "invalid phase {phase:?}, expected parse, part1, part2 or all"
+                ))
+            }
+        };
+        Ok(TimeoutArg { day, phases, secs })
+    }
+}
+
+impl TimeoutArg {
+    pub fn apply(&self, timeouts: &mut Timeouts) {
+        for &phase in &self.phases {
+            timeouts.set(self.day, phase, self.secs);
+        }
+    }
+}
+
+/// Presets for how long criterion measures every phase.
+#[derive(Clone, Copy, Default, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Profile {
+    /// a few samples, for iterating locally
+    Quick,
+    /// the settings of the published leaderboard
+    #[default]
+    Standard,
+    /// more samples and longer measurements
+    Thorough,
+}
+
+impl Profile {
+    fn settings(self) -> CriterionSettings {
+        let (warm_up, measurement_time, sample_size) = match self {
+            Profile::Quick => (0.1, 0.2, 10),
+            Profile::Standard => (1.0, 1.0, 100),
+            Profile::Thorough => (3.0, 5.0, 200),
+        };
+        CriterionSettings {
+            warm_up,
+            measurement_time,
+            sample_size,
+        }
+    }
+}
+
+/// The criterion settings of the command line or the config file, each overrides the profile.
+#[derive(Clone, Default, clap::Args, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CriterionArgs {
+    /// the criterion settings to start from
+    #[clap(long, value_enum)]
+    pub profile: Option<Profile>,
+    /// the warm-up time of every benchmark in seconds
+    #[clap(long)]
+    pub warm_up: Option<f64>,
+    /// the measurement time of every benchmark in seconds
+    #[clap(long)]
+    pub measurement_time: Option<f64>,
+    /// the number of samples of fast benchmarks, slow ones take fewer (at least 10)
+    #[clap(long)]
+    pub sample_size: Option<usize>,
+}
+
+impl CriterionArgs {
+    /// Combines the settings, those of `other` take precedence.
+    pub fn or(self, other: CriterionArgs) -> CriterionArgs {
+        CriterionArgs {
+            profile: other.profile.or(self.profile),
+            warm_up: other.warm_up.or(self.warm_up),
+            measurement_time: other.measurement_time.or(self.measurement_time),
+            sample_size: other.sample_size.or(self.sample_size),
+        }
+    }
+
+    pub fn settings(&self) -> Result<CriterionSettings> {
+        let profile = self.profile.unwrap_or_default().settings();
+        let settings = CriterionSettings {
+            warm_up: self.warm_up.unwrap_or(profile.warm_up),
+            measurement_time: self.measurement_time.unwrap_or(profile.measurement_time),
+            sample_size: self.sample_size.unwrap_or(profile.sample_size),
+        };
+        if ![settings.warm_up, settings.measurement_time]
+            .into_iter()
+            .all(is_positive_secs)
+        {
+            return Err(eyre!(
+                "the warm-up and measurement time have to be positive"
+            ));
+        }
+        if settings.sample_size < 10 {
+            return Err(eyre!("criterion needs a sample size of at least 10"));
+        }
+        Ok(settings)
+    }
+}
+
+pub struct CriterionSettings {
+    /// in seconds
+    pub warm_up: f64,
+    /// in seconds
+    pub measurement_time: f64,
+    pub sample_size: usize,
+}
+
+impl CriterionSettings {
+    /// The number of samples for a phase whose verification run took `duration`, slow phases
+    /// take fewer samples so a single benchmark doesn't take minutes.
+    pub fn sample_size(&self, duration: Duration) -> usize {
+        if duration > Duration::from_secs(1) {
+            10
+        } else if duration > Duration::from_millis(100) {
+            (self.sample_size / 2).max(10)
+        } else {
+            self.sample_size
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(name: &str, content: &str) -> Result<Config> {
+        let path = std::env::temp_dir().join(format!(
+            "aoc-bench-config-{}-{name}.toml",
+            std::process::id()
+        ));
+        std::fs::write(&path, content).unwrap();
+        let config = Config::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        config
+    }
+
+    #[test]
+    fn timeout_arg_applies_to_a_phase_of_all_days_or_one() {
+        let mut timeouts = Timeouts::default();
+        "part1=5"
+            .parse::<TimeoutArg>()
+            .unwrap()
+            .apply(&mut timeouts);
+        "12:all=0.5"
+            .parse::<TimeoutArg>()
+            .unwrap()
+            .apply(&mut timeouts);
+        assert_eq!(timeouts.get(1, Phase::Part1), Some(5.0));
+        assert_eq!(timeouts.get(1, Phase::Part2), Some(30.0));
+        assert_eq!(timeouts.get(12, Phase::Parse), Some(0.5));
+        assert_eq!(timeouts.get(12, Phase::Part2), Some(0.5));
+    }
+
+    #[test]
+    fn timeout_arg_rejects_timeouts_that_are_not_positive_and_finite() {
+        for arg in ["part1=0", "part1=-1", "part1=inf", "part1=NaN", "part1=x"] {
+            assert!(arg.parse::<TimeoutArg>().is_err(), "{arg}");
+        }
+    }
+
+    #[test]
+    fn timeout_arg_rejects_invalid_days_and_phases() {
+        for arg in ["0:part1=1", "26:part1=1", "x:part1=1", "part3=1", "part1"] {
+            assert!(arg.parse::<TimeoutArg>().is_err(), "{arg}");
+        }
+    }
+
+    #[test]
+    fn load_reads_the_timeouts_of_all_days_and_single_ones() {
+        let config = load(
+            "valid",
+            "[timeouts]\npart1 = 2\n\n[[timeouts.days]]\nday = 25\npart2 = 0.5\n",
+        )
+        .unwrap();
+        assert_eq!(config.timeouts.get(1, Phase::Part1), Some(2.0));
+        assert_eq!(config.timeouts.get(25, Phase::Part2), Some(0.5));
+    }
+
+    #[test]
+    fn load_rejects_timeouts_that_are_not_positive_and_finite() {
+        for (name, content) in [
+            ("zero", "[timeouts]\nparse = 0\n"),
+            ("negative", "[timeouts]\npart1 = -1\n"),
+            ("inf", "[timeouts]\npart2 = inf\n"),
+            ("day-zero", "[[timeouts.days]]\nday = 3\npart1 = 0\n"),
+            ("day-inf", "[[timeouts.days]]\nday = 3\nparse = inf\n"),
+        ] {
+            assert!(load(name, content).is_err(), "{content}");
+        }
+    }
+
+    #[test]
+    fn load_rejects_days_out_of_range() {
+        assert!(load("day0", "[[timeouts.days]]\nday = 0\npart1 = 1\n").is_err());
+        assert!(load("day26", "[[timeouts.days]]\nday = 26\npart1 = 1\n").is_err());
+    }
+
+    #[test]
+    fn settings_reject_times_that_are_not_positive_and_finite() {
+        assert!(CriterionArgs::default().settings().is_ok());
+        for (warm_up, measurement_time) in [(0.0, 1.0), (1.0, -1.0), (f64::INFINITY, 1.0)] {
+            let args = CriterionArgs {
+                warm_up: Some(warm_up),
+                measurement_time: Some(measurement_time),
+                ..Default::default()
+            };
+            assert!(args.settings().is_err(), "{warm_up} {measurement_time}");
+        }
+        let args = CriterionArgs {
+            sample_size: Some(9),
+            ..Default::default()
+        };
+        assert!(args.settings().is_err());
+    }
+}
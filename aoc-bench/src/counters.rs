@@ -0,0 +1,238 @@
+//! Hardware performance counters via Linux `perf_event_open`, which are far less noisy than the
+//! wall-clock time on shared machines.
+//!
+//! The counters are opened right before a solution is counted, on the thread that runs it, and are
+//! inherited by the threads spawned after that. The threads a solution starts while it is counted
+//! are included, but thread pools it started earlier, e.g. while its answers were checked, are
+//! not.
+//!
+//! The counters are often unavailable, e.g. in containers or with a restrictive
+//! `/proc/sys/kernel/perf_event_paranoid`, in which case [`Counters::open`] fails and the
+//! benchmarks run without them.
+
+use std::collections::BTreeMap;
+
+use aoc_bench_results::{HardwareCounters, Phase};
+use criterion::black_box;
+
+use crate::DaySolver;
+
+/// How often every phase is counted, the median of the runs is reported.
+const RUNS: usize = 11;
+
+/// Runs every phase [`RUNS`] times with the counters enabled only around it, and returns the
+/// median counts of every phase.
+pub fn count_phases<S: DaySolver>(
+    solver: &S,
+    input: &str,
+    counters: &Counters,
+) -> BTreeMap<Phase, HardwareCounters> {
+    let input = input.trim();
+    let mut counts: BTreeMap<Phase, Vec<HardwareCounters>> = BTreeMap::new();
+    for _ in 0..RUNS {
+        counters.start();
+        let parsed = black_box(solver.parse(black_box(input)));
+        counts
+            .entry(Phase::Parse)
+            .or_default()
+            .push(counters.stop());
+        counters.start();
+        solver.bench_part1(&parsed);
+        counts
+            .entry(Phase::Part1)
+            .or_default()
+            .push(counters.stop());
+        counters.start();
+        solver.bench_part2(&parsed);
+        counts
+            .entry(Phase::Part2)
+            .or_default()
+            .push(counters.stop());
+        counters.start();
+        let parsed = solver.parse(black_box(input));
+        solver.bench_part1(&parsed);
+        solver.bench_part2(&parsed);
+        counts
+            .entry(Phase::Total)
+            .or_default()
+            .push(counters.stop());
+    }
+    counts
+        .into_iter()
+        .map(|(phase, counts)| {
+            let median = |count: fn(&HardwareCounters) -> Option<u64>| {
+                let mut values: Vec<u64> = counts.iter().filter_map(count).collect();
+                values.sort();
+                values.get(values.len() / 2).copied()
+            };
+            let counters = HardwareCounters {
+                instructions: median(|c| Some(c.instructions)).unwrap_or_default(),
+                cycles: median(|c| c.cycles),
+                cache_misses: median(|c| c.cache_misses),
+                branch_misses: median(|c| c.branch_misses),
+            };
+            (phase, counters)
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+pub use linux::Counters;
+
+#[cfg(not(target_os = "linux"))]
+pub struct Counters;
+
+#[cfg(not(target_os = "linux"))]
+impl Counters {
+    pub fn open() -> color_eyre::eyre::Result<Self> {
+        Err(color_eyre::eyre::eyre!(
+            "hardware counters are only supported on Linux"
+        ))
+    }
+
+    fn start(&self) {}
+
+    fn stop(&self) -> HardwareCounters {
+        unreachable!()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{
+        fs::File,
+        io::{self, Read},
+        os::fd::{AsRawFd, FromRawFd},
+    };
+
+    use aoc_bench_results::HardwareCounters;
+    use color_eyre::eyre::{eyre, Result};
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+    const PERF_COUNT_HW_BRANCH_MISSES: u64 = 5;
+
+    // the flag bits of `perf_event_attr`
+    const DISABLED: u64 = 1 << 0;
+    const INHERIT: u64 = 1 << 1;
+    const EXCLUDE_KERNEL: u64 = 1 << 5;
+    const EXCLUDE_HV: u64 = 1 << 6;
+
+    // `_IO('$', n)`
+    const PERF_EVENT_IOC_ENABLE: u64 = 0x2400;
+    const PERF_EVENT_IOC_DISABLE: u64 = 0x2401;
+    const PERF_EVENT_IOC_RESET: u64 = 0x2403;
+    const PERF_IOC_FLAG_GROUP: u64 = 1;
+
+    /// The first version of `struct perf_event_attr`, which every kernel accepts.
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        type_: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+
+    /// A group of counters of the calling thread and the threads it spawns afterwards, led by
+    /// the instruction counter.
+    pub struct Counters {
+        instructions: File,
+        cycles: Option<File>,
+        cache_misses: Option<File>,
+        branch_misses: Option<File>,
+    }
+
+    fn open(config: u64, group: Option<&File>) -> io::Result<File> {
+        let attr = PerfEventAttr {
+            type_: PERF_TYPE_HARDWARE,
+            size: std::mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            // only the leader starts disabled, the others follow it. The counts of the threads
+            // spawned later are added to the reads, and the ioctls apply to them as well
+            flags: if group.is_none() { DISABLED } else { 0 }
+                | INHERIT
+                | EXCLUDE_KERNEL
+                | EXCLUDE_HV,
+            ..Default::default()
+        };
+        let group_fd = group.map_or(-1, |g| g.as_raw_fd());
+        // SAFETY: `attr` is a valid `perf_event_attr` of the size given in it
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_perf_event_open,
+                &attr as *const PerfEventAttr,
+                0,  // the calling thread
+                -1, // on any CPU
+                group_fd,
+                0,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: the syscall returned a new file descriptor that nothing else owns
+        Ok(unsafe { File::from_raw_fd(fd as i32) })
+    }
+
+    fn read(mut file: &File) -> u64 {
+        let mut buf = [0; 8];
+        match file.read_exact(&mut buf) {
+            Ok(()) => u64::from_ne_bytes(buf),
+            Err(_) => 0,
+        }
+    }
+
+    impl Counters {
+        /// Opens the counters for the calling thread and its future threads. Fails if the
+        /// instruction counter is not available, the other counters are left out if the CPU
+        /// doesn't support them.
+        pub fn open() -> Result<Self> {
+            let instructions = open(PERF_COUNT_HW_INSTRUCTIONS, None)
+                .map_err(|e| eyre!("cannot open the instruction counter: {e}"))?;
+            let other = |config| open(config, Some(&instructions)).ok();
+            Ok(Counters {
+                cycles: other(PERF_COUNT_HW_CPU_CYCLES),
+                cache_misses: other(PERF_COUNT_HW_CACHE_MISSES),
+                branch_misses: other(PERF_COUNT_HW_BRANCH_MISSES),
+                instructions,
+            })
+        }
+
+        fn ioctl(&self, request: u64) {
+            // SAFETY: the requests take no argument besides the group flag
+            unsafe {
+                libc::ioctl(
+                    self.instructions.as_raw_fd(),
+                    request as _,
+                    PERF_IOC_FLAG_GROUP,
+                );
+            }
+        }
+
+        /// Resets and starts counting.
+        pub(super) fn start(&self) {
+            self.ioctl(PERF_EVENT_IOC_RESET);
+            self.ioctl(PERF_EVENT_IOC_ENABLE);
+        }
+
+        /// Stops counting and returns the counts since [`Counters::start`].
+        pub(super) fn stop(&self) -> HardwareCounters {
+            self.ioctl(PERF_EVENT_IOC_DISABLE);
+            HardwareCounters {
+                instructions: read(&self.instructions),
+                cycles: self.cycles.as_ref().map(read),
+                cache_misses: self.cache_misses.as_ref().map(read),
+                branch_misses: self.branch_misses.as_ref().map(read),
+            }
+        }
+    }
+}
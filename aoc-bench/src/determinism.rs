@@ -0,0 +1,62 @@
+//! Checks that a solution gives the same answers every time. Solutions that iterate a `HashMap`
+//! with a random seed or depend on thread timing can pass a single verification run by luck.
+
+use crate::{cold, solver::Day, BenchOptions, ExecutionError, TestCase};
+
+/// Runs a verified day `options.determinism_runs` more times, each with a fresh parse, and
+/// checks that every run gives the same answers. Returns the outcome of part1 and part2.
+pub fn check(
+    day: &dyn Day,
+    username: &str,
+    case: &TestCase,
+    options: &BenchOptions,
+) -> (Result<(), ExecutionError>, Result<(), ExecutionError>) {
+    let runs = options.determinism_runs;
+    let timeout = options.run_timeout(case.day);
+    let mut part1 = Ok(());
+    let mut part2 = Ok(());
+    for run in 1..=runs {
+        let answers = if options.determinism_processes {
+            cold::run_process(
+                options.plugin_dir.as_deref(),
+                username,
+                case.day,
+                case.input,
+                timeout,
+            )
+            .and_then(|outcome| outcome.into_result())
+            .map(|output| (output.part1, output.part2))
+            .map_err(|e| e.to_string())
+        } else {
+            day.answers(case.input.trim(), timeout)
+                .map_err(|e| e.details().unwrap_or_else(|| e.status().to_string()))
+        };
+        let differs = |what: String| {
+            Err(ExecutionError::NonDeterministic(format!(
+                "{what} in run {run} of {runs}"
+            )))
+        };
+        match answers {
+            Ok((answer1, answer2)) => {
+                if part1.is_ok() && !case.answers.check_part1(&answer1) {
+                    part1 = differs(format!("answered {answer1:?}"));
+                }
+                if part2.is_ok() && !case.answers.check_part2(&answer2) {
+                    part2 = differs(format!("answered {answer2:?}"));
+                }
+            }
+            Err(e) => {
+                if part1.is_ok() {
+                    part1 = differs(e.clone());
+                }
+                if part2.is_ok() {
+                    part2 = differs(e);
+                }
+            }
+        }
+        if part1.is_err() && part2.is_err() {
+            break;
+        }
+    }
+    (part1, part2)
+}
@@ -0,0 +1,233 @@
+//! Differential testing: runs all participants on inputs without known answers and uses the
+//! majority answer as the oracle, to find bugs the official inputs don't exercise.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use aoc_bench_results::Status;
+use color_eyre::eyre::{eyre, Context, Result};
+
+use crate::{solver::Participant, BenchOptions};
+
+/// Where the inputs without answers come from.
+pub enum Source {
+    /// a directory of plaintext inputs named like `day05-anything.txt`
+    Dir(PathBuf),
+    /// a program that is called as `<program> <day> <seed>` and prints an input
+    Generator {
+        program: PathBuf,
+        /// the number of inputs per day
+        count: usize,
+        seed: u64,
+    },
+}
+
+/// An input of a day, named after its file or generator seed.
+struct Input {
+    day: u8,
+    name: String,
+    content: String,
+}
+
+fn read_dir(dir: &Path, days: &[u8]) -> Result<Vec<Input>> {
+    let mut inputs = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("cannot read {}", dir.display()))? {
+        let path = entry?.path();
+        let Some(name) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(".txt"))
+        else {
+            continue;
+        };
+        let Some(day) = name
+            .strip_prefix("day")
+            .and_then(|n| n.get(..2))
+            .and_then(|d| d.parse().ok())
+            .filter(|d| days.contains(d))
+        else {
+            continue;
+        };
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        inputs.push(Input {
+            day,
+            name: name.to_string(),
+            content,
+        });
+    }
+    inputs.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(inputs)
+}
+
+fn generate(program: &Path, count: usize, seed: u64, days: &[u8]) -> Result<Vec<Input>> {
+    let mut inputs = Vec::new();
+    for &day in days {
+        for seed in seed..seed + count as u64 {
+            let output = Command::new(program)
+                .args([day.to_string(), seed.to_string()])
+                .output()
+                .with_context(|| format!("cannot run generator {}", program.display()))?;
+            if !output.status.success() {
+                // the generator doesn't support every day
+                println!("The generator failed for day {day} with {}", output.status);
+                break;
+            }
+            inputs.push(Input {
+                day,
+                name: format!("day{day:02}-gen{seed}"),
+                content: String::from_utf8(output.stdout)
+                    .map_err(|_| eyre!("the generator printed invalid UTF-8 for day {day}"))?,
+            });
+        }
+    }
+    Ok(inputs)
+}
+
+/// The users that gave each outcome of a part, most common first. Failures count as outcomes of
+/// their own, so a crash is in the minority as well.
+fn vote(outcomes: &[(&str, String)]) -> Vec<(String, Vec<String>)> {
+    let mut votes: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for (user, outcome) in outcomes {
+        votes
+            .entry(outcome.as_str())
+            .or_default()
+            .push(user.to_string());
+    }
+    let mut votes: Vec<_> = votes
+        .into_iter()
+        .map(|(outcome, users)| (outcome.to_string(), users))
+        .collect();
+    votes.sort_by_key(|(_, users)| std::cmp::Reverse(users.len()));
+    votes
+}
+
+/// Runs every participant on the inputs of `source` and prints a table of the inputs on which
+/// they disagree.
+pub fn run(
+    participants: &[Participant],
+    source: &Source,
+    days: &[u8],
+    options: &BenchOptions,
+) -> Result<()> {
+    let inputs = match source {
+        Source::Dir(dir) => read_dir(dir, days)?,
+        Source::Generator {
+            program,
+            count,
+            seed,
+        } => {
+            println!("Generating inputs with seed {seed}");
+            generate(program, *count, *seed, days)?
+        }
+    };
+    let mut disagreements = Vec::new();
+    for input in &inputs {
+        println!("Comparing answers on {}", input.name);
+        let mut part1 = Vec::new();
+        let mut part2 = Vec::new();
+        for participant in participants {
+            let user = participant.name.as_str();
+            let answers = participant
+                .day(input.day)
+                .answers(input.content.trim(), options.run_timeout(input.day));
+            match answers {
+                Ok((answer1, answer2)) => {
+                    part1.push((user, format!("`{answer1}`")));
+                    part2.push((user, format!("`{answer2}`")));
+                }
+                Err(e) if e.status() == Status::NotImplemented => {}
+                Err(e) => {
+                    let outcome = e.status().to_string();
+                    part1.push((user, outcome.clone()));
+                    part2.push((user, outcome));
+                }
+            }
+        }
+        for (part, outcomes) in [("part1", &part1), ("part2", &part2)] {
+            let votes = vote(outcomes);
+            if votes.len() > 1 {
+                disagreements.push((input.name.clone(), part, outcomes.len(), votes));
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "# Differential testing: {} disagreements on {} inputs",
+        disagreements.len(),
+        inputs.len()
+    );
+    if disagreements.is_empty() {
+        return Ok(());
+    }
+    println!();
+    println!("| Input | Part | Majority | Minority |");
+    println!("|-------|------|----------|----------|");
+    for (input, part, voters, votes) in &disagreements {
+        let (answer, users) = &votes[0];
+        // without a strict majority every outcome is a minority
+        let (majority, minority) = if users.len() * 2 > *voters {
+            (format!("{answer} ({})", users.join(", ")), &votes[1..])
+        } else {
+            ("no majority".to_string(), &votes[..])
+        };
+        let minority: Vec<String> = minority
+            .iter()
+            .map(|(answer, users)| format!("**{}**: {answer}", users.join(", ")))
+            .collect();
+        println!(
+            "| {input} | {part} | {majority} | {} |",
+            minority.join("; ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcomes<'a>(votes: &[(&'a str, &str)]) -> Vec<(&'a str, String)> {
+        votes
+            .iter()
+            .map(|&(user, outcome)| (user, outcome.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn vote_puts_the_majority_first() {
+        let votes = vote(&outcomes(&[("a", "2"), ("b", "1"), ("c", "1")]));
+        assert_eq!(
+            votes,
+            [
+                ("1".to_string(), vec!["b".to_string(), "c".to_string()]),
+                ("2".to_string(), vec!["a".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn vote_counts_failures_as_outcomes_of_their_own() {
+        let votes = vote(&outcomes(&[
+            ("a", "💥 panicked"),
+            ("b", "🐌 timeout"),
+            ("c", "5"),
+            ("d", "5"),
+        ]));
+        assert_eq!(votes.len(), 3);
+        assert_eq!(
+            votes[0],
+            ("5".to_string(), vec!["c".to_string(), "d".to_string()])
+        );
+    }
+
+    #[test]
+    fn vote_of_a_single_participant_has_no_minority() {
+        assert_eq!(vote(&outcomes(&[("a", "1")])).len(), 1);
+        assert!(vote(&[]).is_empty());
+    }
+}
@@ -0,0 +1,31 @@
+//! Collects the [`Fingerprint`] of a run. The rustc version and RUSTFLAGS are recorded by the
+//! build script, the rest is read from the machine at runtime.
+
+use std::collections::BTreeMap;
+
+use aoc_bench_results::Fingerprint;
+
+/// The fingerprint of this machine, with the git revisions of the solutions by participant.
+pub fn collect(revisions: BTreeMap<String, String>) -> Fingerprint {
+    Fingerprint {
+        rustc: env!("AOC_BENCH_RUSTC_VERSION").to_string(),
+        rustflags: env!("AOC_BENCH_RUSTFLAGS").to_string(),
+        cpu: cpu_model().unwrap_or_default(),
+        cores: std::thread::available_parallelism().map_or(0, |n| n.get()),
+        kernel: kernel().unwrap_or_else(|| std::env::consts::OS.to_string()),
+        revisions,
+    }
+}
+
+fn cpu_model() -> Option<String> {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+    cpuinfo.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "model name").then(|| value.trim().to_string())
+    })
+}
+
+fn kernel() -> Option<String> {
+    let release = std::fs::read_to_string("/proc/sys/kernel/osrelease").ok()?;
+    Some(format!("{} {}", std::env::consts::OS, release.trim()))
+}
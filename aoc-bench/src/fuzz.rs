@@ -0,0 +1,151 @@
+//! Fuzzes the solutions with mutations of the real inputs, to find inputs that make them crash
+//! or hang. Every run happens in a fresh process, so `unsafe` code that corrupts memory can't take
+//! the harness down, and the inputs that fail are kept as a corpus to reproduce them with.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use color_eyre::eyre::{Context, Result};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    answers::Answers,
+    cold::{self, ProcessOutcome},
+    inputs::InputProvider,
+    solver::Participant,
+    BenchOptions,
+};
+
+pub struct FuzzOptions {
+    /// the number of mutated inputs per user and day
+    pub iterations: usize,
+    pub seed: u64,
+    /// a run taking longer than this counts as a hang
+    pub time_limit: Duration,
+    pub corpus_dir: PathBuf,
+}
+
+/// Mutates an input by flipping bits, truncating it or swapping lines.
+fn mutate(input: &str, rng: &mut StdRng) -> String {
+    if input.is_empty() {
+        return String::new();
+    }
+    match rng.gen_range(0..3) {
+        0 => {
+            let mut bytes = input.as_bytes().to_vec();
+            for _ in 0..rng.gen_range(1..=8) {
+                let i = rng.gen_range(0..bytes.len());
+                // only flip the lower 7 bits of ASCII bytes, so the input stays valid UTF-8
+                if bytes[i].is_ascii() {
+                    bytes[i] ^= 1 << rng.gen_range(0..7);
+                }
+            }
+            String::from_utf8(bytes).unwrap()
+        }
+        1 => {
+            let mut end = rng.gen_range(0..input.len());
+            while !input.is_char_boundary(end) {
+                end -= 1;
+            }
+            input[..end].to_string()
+        }
+        _ => {
+            let mut lines: Vec<&str> = input.lines().collect();
+            for _ in 0..rng.gen_range(1..=4) {
+                let (a, b) = (rng.gen_range(0..lines.len()), rng.gen_range(0..lines.len()));
+                lines.swap(a, b);
+            }
+            if rng.gen_bool(0.1) {
+                lines.shuffle(rng);
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+/// Stores a failing input in the corpus, named after its hash so repeated finds are only stored
+/// once. Returns the path of the input.
+fn save(dir: &Path, kind: &str, day: u8, input: &str, log: Option<&str>) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("cannot create corpus directory {}", dir.display()))?;
+    let hash = format!("{:x}", Sha256::digest(input));
+    let name = format!("{kind}-day{day:02}-{}", &hash[..16]);
+    let path = dir.join(format!("{name}.txt"));
+    std::fs::write(&path, input).with_context(|| format!("cannot write {}", path.display()))?;
+    if let Some(log) = log {
+        let log_path = dir.join(format!("{name}.log"));
+        std::fs::write(&log_path, log)
+            .with_context(|| format!("cannot write {}", log_path.display()))?;
+    }
+    Ok(path)
+}
+
+/// Fuzzes every day of the participants that solves its real inputs, seeded with those inputs.
+pub fn fuzz(
+    participants: &[Participant],
+    inputs: &dyn InputProvider,
+    answers: &Answers,
+    options: &BenchOptions,
+    fuzz: &FuzzOptions,
+) -> Result<()> {
+    println!("Fuzzing with seed {}", fuzz.seed);
+    let mut rng = StdRng::seed_from_u64(fuzz.seed);
+    let mut days: BTreeMap<u8, Vec<_>> = BTreeMap::new();
+    for answers in &answers.inputs {
+        days.entry(answers.day).or_default().push(answers);
+    }
+    for participant in participants {
+        let username = participant.name.as_str();
+        for (&day, day_answers) in &days {
+            // only fuzz days that solve the real inputs, the others would fail on every mutation
+            let mut seeds = Vec::new();
+            for answers in day_answers {
+                let input = inputs.input(&answers.name)?;
+                let timeout = options.run_timeout(day);
+                if let Ok((part1, part2)) = participant.day(day).answers(input.trim(), timeout) {
+                    if answers.check_part1(&part1) && answers.check_part2(&part2) {
+                        seeds.push(input);
+                    }
+                }
+            }
+            if seeds.is_empty() {
+                continue;
+            }
+            println!("Fuzzing user {username}, day {day:02}");
+            let dir = fuzz.corpus_dir.join(username);
+            let (mut crashes, mut hangs) = (0, 0);
+            for _ in 0..fuzz.iterations {
+                let input = mutate(seeds.choose(&mut rng).unwrap(), &mut rng);
+                let outcome = cold::run_process(
+                    options.plugin_dir.as_deref(),
+                    username,
+                    day,
+                    &input,
+                    fuzz.time_limit,
+                )?;
+                let path = match outcome {
+                    ProcessOutcome::Finished(_) => continue,
+                    ProcessOutcome::Crashed { status, stderr } => {
+                        crashes += 1;
+                        let log = format!("{status}\n{stderr}");
+                        save(&dir, "crash", day, &input, Some(&log))?
+                    }
+                    ProcessOutcome::TimedOut => {
+                        hangs += 1;
+                        save(&dir, "hang", day, &input, None)?
+                    }
+                };
+                println!("{username}-day{day:02}-fuzz: {}", path.display());
+            }
+            println!(
+                "{username}-day{day:02}: {crashes} crashes and {hangs} hangs in {} runs",
+                fuzz.iterations
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! Held-out inputs, which are never published and only used for verification, so a solution that
+//! hardcodes the answers of the public inputs gets caught.
+//!
+//! A held-out directory contains the inputs as `<name>.txt.age` and their answers manifest as
+//! `answers.toml.age`, like the public ones. Everything is encrypted with the passphrase in
+//! `HELD_OUT_PASSPHRASE`, which is separate from the key of the public inputs.
+
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Context, Result};
+
+use crate::{
+    answers::{Answers, ExpectedAnswers},
+    inputs::{self, AgeEncryptedDir, Cached},
+};
+
+/// The environment variable holding the passphrase of the held-out inputs.
+pub const PASSPHRASE_VAR: &str = "HELD_OUT_PASSPHRASE";
+
+const MANIFEST: &str = "answers.toml.age";
+
+pub struct HeldOut {
+    pub inputs: Cached<AgeEncryptedDir>,
+    pub answers: Answers,
+}
+
+impl HeldOut {
+    /// Loads the held-out inputs in `dir`, decrypting them with the identities in
+    /// `identity_file` or the passphrase in `HELD_OUT_PASSPHRASE`.
+    pub fn load(dir: &Path, identity_file: Option<&Path>, public: &Answers) -> Result<Self> {
+        let inputs = match identity_file {
+            Some(identity_file) => AgeEncryptedDir::with_identity_file(dir, identity_file)?,
+            None => AgeEncryptedDir::with_passphrase(dir, passphrase()?),
+        };
+        let path = dir.join(MANIFEST);
+        let answers = Answers::load_encrypted(&path, &inputs)?;
+        // the results only name the input, so the names have to tell them apart
+        if let Some(a) = answers
+            .inputs
+            .iter()
+            .find(|a| public.inputs.iter().any(|p| p.name == a.name))
+        {
+            return Err(eyre!(
+                "held-out input {} has the same name as a public input",
+                a.name
+            ));
+        }
+        Ok(HeldOut {
+            inputs: Cached::new(inputs),
+            answers,
+        })
+    }
+}
+
+fn passphrase() -> Result<String> {
+    std::env::var(PASSPHRASE_VAR).map_err(|_| {
+        eyre!(
+            "no key for the held-out inputs: set {PASSPHRASE_VAR} or pass an identity file \
+             with --held-out-identity"
+        )
+    })
+}
+
+/// Adds a held-out input and its answers, or updates an existing one. Returns the path of the
+/// stored input, if one was given.
+pub fn add(dir: &Path, answers: ExpectedAnswers, input: Option<&str>) -> Result<Option<PathBuf>> {
+    let passphrase = passphrase()?;
+    std::fs::create_dir_all(dir).with_context(|| format!("cannot create {}", dir.display()))?;
+    let manifest = dir.join(MANIFEST);
+    let mut all = if manifest.exists() {
+        let key = AgeEncryptedDir::with_passphrase(dir, passphrase.clone());
+        Answers::load_encrypted(&manifest, &key)?
+    } else {
+        Answers::default()
+    };
+    let path = match input {
+        Some(input) => {
+            let path = dir.join(format!("{}.txt.age", answers.name));
+            std::fs::write(&path, inputs::encrypt(input, passphrase.clone())?)
+                .with_context(|| format!("cannot write {}", path.display()))?;
+            Some(path)
+        }
+        None => None,
+    };
+    all.upsert(answers);
+    all.save_encrypted(&manifest, passphrase)?;
+    Ok(path)
+}
@@ -0,0 +1,218 @@
+//! Providers for the puzzle inputs.
+//!
+//! Inputs are named after the file they are stored in without extension, e.g. `day01`.
+//! The encrypted inputs are deliberately slow to decrypt (scrypt), so every provider is
+//! wrapped in a [`Cached`] provider that decrypts each input only once per run.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::{eyre, Context, Result};
+
+/// The environment variable holding the passphrase the inputs are encrypted with.
+pub const PASSPHRASE_VAR: &str = "AGE_PASSPHRASE";
+
+pub trait InputProvider {
+    /// Returns the input with the given name, e.g. `day01`.
+    fn input(&self, name: &str) -> Result<String>;
+}
+
+/// Unencrypted inputs stored as `<dir>/<name>.txt`.
+pub struct PlaintextDir {
+    dir: PathBuf,
+}
+
+impl PlaintextDir {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        PlaintextDir { dir: dir.into() }
+    }
+}
+
+impl InputProvider for PlaintextDir {
+    fn input(&self, name: &str) -> Result<String> {
+        let path = self.dir.join(format!("{name}.txt"));
+        std::fs::read_to_string(&path).with_context(|| format!("cannot read {}", path.display()))
+    }
+}
+
+/// Inputs encrypted with age, stored as `<dir>/<name>.txt.age`.
+pub struct AgeEncryptedDir {
+    dir: PathBuf,
+    identities: Vec<Box<dyn age::Identity>>,
+}
+
+impl AgeEncryptedDir {
+    /// Decrypts the inputs with a passphrase.
+    pub fn with_passphrase(dir: impl Into<PathBuf>, passphrase: String) -> Self {
+        AgeEncryptedDir {
+            dir: dir.into(),
+            identities: vec![Box::new(age::scrypt::Identity::new(passphrase.into()))],
+        }
+    }
+
+    /// Decrypts the inputs with the passphrase in the `AGE_PASSPHRASE` environment variable.
+    pub fn with_passphrase_from_env(dir: impl Into<PathBuf>) -> Result<Self> {
+        let passphrase = std::env::var(PASSPHRASE_VAR).map_err(|_| {
+            eyre!(
+                "no key to decrypt the inputs: set {PASSPHRASE_VAR}, pass an age or SSH identity \
+                 file with --identity, or use unencrypted inputs with --plaintext"
+            )
+        })?;
+        Ok(Self::with_passphrase(dir, passphrase))
+    }
+
+    /// Decrypts the inputs with the identities in an age identity file (X25519 keys) or an
+    /// unencrypted SSH private key.
+    pub fn with_identity_file(dir: impl Into<PathBuf>, identity_file: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(identity_file)
+            .with_context(|| format!("cannot read identity file {}", identity_file.display()))?;
+        let identities: Vec<Box<dyn age::Identity>> = if content
+            .trim_start()
+            .starts_with("-----BEGIN")
+        {
+            let identity = age::ssh::Identity::from_buffer(
+                content.as_bytes(),
+                Some(identity_file.display().to_string()),
+            )
+            .with_context(|| format!("invalid SSH key {}", identity_file.display()))?;
+            match identity {
+                age::ssh::Identity::Unencrypted(_) => vec![Box::new(identity)],
+                age::ssh::Identity::Encrypted(_) => {
+                    return Err(eyre!(
+                        "SSH key {} is passphrase protected, which is not supported",
+                        identity_file.display()
+                    ))
+                }
+                age::ssh::Identity::Unsupported(_) => {
+                    return Err(eyre!(
+                        "SSH key {} is of an unsupported type",
+                        identity_file.display()
+                    ))
+                }
+            }
+        } else {
+            age::IdentityFile::from_buffer(content.as_bytes())
+                .and_then(|f| {
+                    f.into_identities()
+                        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+                })
+                .with_context(|| format!("invalid age identity file {}", identity_file.display()))?
+        };
+        if identities.is_empty() {
+            return Err(eyre!(
+                "identity file {} contains no identities",
+                identity_file.display()
+            ));
+        }
+        Ok(AgeEncryptedDir {
+            dir: dir.into(),
+            identities,
+        })
+    }
+
+    /// Decrypts any file encrypted with the key of the inputs.
+    pub fn decrypt(&self, path: &Path) -> Result<String> {
+        let ciphertext =
+            std::fs::read(path).with_context(|| format!("cannot read {}", path.display()))?;
+        let decryptor = age::Decryptor::new(ciphertext.as_slice())
+            .with_context(|| format!("{} is not an age file", path.display()))?;
+        let mut reader = decryptor
+            .decrypt(self.identities.iter().map(|i| i.as_ref()))
+            .with_context(|| format!("cannot decrypt {}, wrong key?", path.display()))?;
+        let mut input = String::new();
+        reader
+            .read_to_string(&mut input)
+            .with_context(|| format!("cannot decrypt {}", path.display()))?;
+        Ok(input)
+    }
+}
+
+impl InputProvider for AgeEncryptedDir {
+    fn input(&self, name: &str) -> Result<String> {
+        self.decrypt(&self.dir.join(format!("{name}.txt.age")))
+    }
+}
+
+/// Encrypts `plaintext` with a passphrase.
+pub fn encrypt(plaintext: &str, passphrase: String) -> Result<Vec<u8>> {
+    let mut ciphertext = Vec::new();
+    let mut writer =
+        age::Encryptor::with_user_passphrase(passphrase.into()).wrap_output(&mut ciphertext)?;
+    writer.write_all(plaintext.as_bytes())?;
+    writer.finish()?;
+    Ok(ciphertext)
+}
+
+/// The passphrase in `AGE_PASSPHRASE` to encrypt inputs and the answers manifest with.
+pub fn passphrase_from_env() -> Result<String> {
+    std::env::var(PASSPHRASE_VAR)
+        .map_err(|_| eyre!("no key to encrypt with: set {PASSPHRASE_VAR}, or use --plaintext"))
+}
+
+/// Stores an input in `dir`, encrypted with the passphrase in `AGE_PASSPHRASE` unless
+/// `plaintext` is set. Returns the path of the written file.
+pub fn store(dir: &Path, name: &str, input: &str, plaintext: bool) -> Result<PathBuf> {
+    if plaintext {
+        let path = dir.join(format!("{name}.txt"));
+        std::fs::write(&path, input).with_context(|| format!("cannot write {}", path.display()))?;
+        return Ok(path);
+    }
+    let path = dir.join(format!("{name}.txt.age"));
+    std::fs::write(&path, encrypt(input, passphrase_from_env()?)?)
+        .with_context(|| format!("cannot write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Caches the inputs of another provider, so each one is only read and decrypted once.
+pub struct Cached<P> {
+    inner: P,
+    cache: RefCell<HashMap<String, String>>,
+}
+
+impl<P: InputProvider> Cached<P> {
+    pub fn new(inner: P) -> Self {
+        Cached {
+            inner,
+            cache: RefCell::default(),
+        }
+    }
+}
+
+impl<P: InputProvider> InputProvider for Cached<P> {
+    fn input(&self, name: &str) -> Result<String> {
+        if let Some(input) = self.cache.borrow().get(name) {
+            return Ok(input.clone());
+        }
+        let input = self.inner.input(name)?;
+        self.cache
+            .borrow_mut()
+            .insert(name.to_string(), input.clone());
+        Ok(input)
+    }
+}
+
+/// Selects the input provider from the command line arguments. Fails early if the key to
+/// decrypt the inputs is missing, rather than after the first participant was benchmarked.
+pub fn from_args(
+    dir: &Path,
+    plaintext: bool,
+    identity: Option<&Path>,
+) -> Result<Box<dyn InputProvider>> {
+    Ok(match plaintext {
+        true => Box::new(Cached::new(PlaintextDir::new(dir))),
+        false => Box::new(Cached::new(key_from_args(dir, identity)?)),
+    })
+}
+
+/// The key of the encrypted inputs in `dir`, from the identity file if one is given or else
+/// from `AGE_PASSPHRASE`.
+pub fn key_from_args(dir: &Path, identity: Option<&Path>) -> Result<AgeEncryptedDir> {
+    match identity {
+        Some(identity) => AgeEncryptedDir::with_identity_file(dir, identity),
+        None => AgeEncryptedDir::with_passphrase_from_env(dir),
+    }
+}
@@ -0,0 +1,182 @@
+//! Shields the measurements from the rest of the machine: pins the benchmarking thread to a
+//! CPU, raises its priority and warns about CPU frequency scaling.
+//!
+//! Threads started with [`spawn`], like the ones verifying a phase, are kept off the pinned CPU,
+//! so a thread abandoned after a timeout doesn't compete with the following benchmarks.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+    thread::{self, JoinHandle},
+};
+
+use aoc_bench_results::Isolation;
+use color_eyre::eyre::Result;
+
+/// The CPUs the other threads may use, only set if the benchmarking thread is pinned.
+static OTHER_CPUS: OnceLock<sys::CpuSet> = OnceLock::new();
+/// Whether the priority of the benchmarking thread was raised.
+static PRIORITY_RAISED: AtomicBool = AtomicBool::new(false);
+
+/// Applies the settings to the calling thread, which should be the one running the benchmarks,
+/// and returns them together with the frequency scaling state. Pinning to a CPU that doesn't
+/// exist fails, a priority that cannot be raised is only a warning.
+pub fn setup(pin_cpu: Option<usize>, high_priority: bool) -> Result<Isolation> {
+    let mut isolation = Isolation::default();
+    if let Some(cpu) = pin_cpu {
+        let others = sys::pin(cpu)?;
+        let _ = OTHER_CPUS.set(others);
+        isolation.pinned_cpu = Some(cpu);
+    }
+    if high_priority {
+        match sys::raise_priority() {
+            Ok(nice) => {
+                PRIORITY_RAISED.store(true, Ordering::Relaxed);
+                isolation.nice = Some(nice);
+            }
+            Err(e) => {
+                println!("Warning: cannot raise the priority ({e}), continuing without")
+            }
+        }
+    }
+    isolation.governor = sys::governor(pin_cpu.unwrap_or(0));
+    if let Some(governor) = isolation
+        .governor
+        .as_deref()
+        .filter(|g| *g != "performance")
+    {
+        println!(
+            "Warning: the CPU frequency governor is {governor}, the times are noisier than with \
+             the performance governor"
+        );
+    }
+    isolation.turbo = sys::turbo();
+    if isolation.turbo == Some(true) {
+        println!("Warning: turbo boost is enabled, the times depend on how hot the CPU is running");
+    }
+    Ok(isolation)
+}
+
+/// Like [`thread::spawn`], but keeps the thread off the CPU the benchmarks are pinned to and
+/// at the default priority.
+pub fn spawn<F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    thread::spawn(move || {
+        if let Some(others) = OTHER_CPUS.get() {
+            sys::set_affinity(others);
+        }
+        if PRIORITY_RAISED.load(Ordering::Relaxed) {
+            sys::reset_priority();
+        }
+        f()
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::io;
+
+    use color_eyre::eyre::{eyre, Result};
+
+    pub struct CpuSet(libc::cpu_set_t);
+
+    /// Pins the calling thread to `cpu` and returns the other CPUs it was allowed to run on.
+    pub fn pin(cpu: usize) -> Result<CpuSet> {
+        // SAFETY: the set is a plain bit set of the given size
+        unsafe {
+            let mut allowed: libc::cpu_set_t = std::mem::zeroed();
+            if libc::sched_getaffinity(0, std::mem::size_of_val(&allowed), &mut allowed) != 0 {
+                return Err(eyre!(
+                    "cannot read the CPU affinity: {}",
+                    io::Error::last_os_error()
+                ));
+            }
+            if cpu >= libc::CPU_SETSIZE as usize || !libc::CPU_ISSET(cpu, &allowed) {
+                return Err(eyre!("cannot pin to CPU {cpu}, it is not available"));
+            }
+            let mut pinned: libc::cpu_set_t = std::mem::zeroed();
+            libc::CPU_SET(cpu, &mut pinned);
+            if libc::sched_setaffinity(0, std::mem::size_of_val(&pinned), &pinned) != 0 {
+                return Err(eyre!(
+                    "cannot pin to CPU {cpu}: {}",
+                    io::Error::last_os_error()
+                ));
+            }
+            // with a single CPU the other threads have to share it
+            if libc::CPU_COUNT(&allowed) > 1 {
+                libc::CPU_CLR(cpu, &mut allowed);
+            }
+            Ok(CpuSet(allowed))
+        }
+    }
+
+    pub fn set_affinity(cpus: &CpuSet) {
+        // SAFETY: see `pin`
+        unsafe { libc::sched_setaffinity(0, std::mem::size_of_val(&cpus.0), &cpus.0) };
+    }
+
+    /// Sets the nice value of the calling thread as low as allowed, which needs
+    /// `CAP_SYS_NICE` or a matching `RLIMIT_NICE`. Returns the new nice value.
+    pub fn raise_priority() -> Result<i32> {
+        for nice in [-20, -10, -5, -1] {
+            // on Linux the nice value is per thread, 0 is the calling thread
+            // SAFETY: no pointers involved
+            if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) } == 0 {
+                return Ok(nice);
+            }
+        }
+        Err(eyre!("{}", io::Error::last_os_error()))
+    }
+
+    /// Sets the nice value of the calling thread back to the default, which is always allowed.
+    pub fn reset_priority() {
+        // SAFETY: no pointers involved
+        unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, 0) };
+    }
+
+    /// The frequency governor of `cpu`, e.g. `performance` or `powersave`.
+    pub fn governor(cpu: usize) -> Option<String> {
+        let path = format!("/sys/devices/system/cpu/cpu{cpu}/cpufreq/scaling_governor");
+        Some(std::fs::read_to_string(path).ok()?.trim().to_string())
+    }
+
+    /// Whether turbo boost is enabled, according to the Intel or the generic cpufreq driver.
+    pub fn turbo() -> Option<bool> {
+        let read = |path| std::fs::read_to_string(path).ok().map(|s| s.trim() == "1");
+        read("/sys/devices/system/cpu/intel_pstate/no_turbo")
+            .map(|no_turbo| !no_turbo)
+            .or_else(|| read("/sys/devices/system/cpu/cpufreq/boost"))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use color_eyre::eyre::{eyre, Result};
+
+    pub struct CpuSet;
+
+    pub fn pin(_cpu: usize) -> Result<CpuSet> {
+        Err(eyre!("CPU pinning is only supported on Linux"))
+    }
+
+    pub fn set_affinity(_cpus: &CpuSet) {}
+
+    pub fn raise_priority() -> Result<i32> {
+        Err(eyre!("only supported on Linux"))
+    }
+
+    pub fn reset_priority() {}
+
+    pub fn governor(_cpu: usize) -> Option<String> {
+        None
+    }
+
+    pub fn turbo() -> Option<bool> {
+        None
+    }
+}
@@ -1,63 +1,430 @@
 use std::{
+    any::Any,
+    collections::{BTreeMap, HashMap},
     panic::{self},
+    path::{Path, PathBuf},
     sync::mpsc,
-    thread,
     time::{Duration, Instant},
 };
 
-use aoc_traits::{AdventOfCodeDay, AdventOfCodeSolutions};
+use clap::{Parser, Subcommand};
+use color_eyre::eyre::{eyre, Context, Result};
 use criterion::{black_box, BatchSize, Criterion};
 
-enum ExecutionError {
+use aoc_bench_results::{
+    CalibrationPoint, Event, Order, Phase, PhaseResult, RunInfo, Shard, Status, Timeouts,
+};
+
+use answers::{Answers, ExpectedAnswers};
+use builder::BuildOutcome;
+use cache::Cache;
+use config::{Config, CriterionArgs, CriterionSettings, TimeoutArg};
+use counters::Counters;
+use held_out::HeldOut;
+use inputs::InputProvider;
+use results::ResultsWriter;
+use solver::{DaySolver, Participant};
+
+mod answers;
+mod builder;
+mod cache;
+mod calibration;
+mod cold;
+mod config;
+mod counters;
+mod determinism;
+mod differential;
+mod fingerprint;
+mod fuzz;
+mod held_out;
+mod inputs;
+mod isolation;
+mod participants;
+mod plugin;
+mod results;
+mod robustness;
+mod schedule;
+mod solver;
+mod verify;
+mod wasm;
+
+#[derive(Parser)]
+struct Args {
+    /// a directory of solution plugins (cdylibs using `aoc_traits::export_solutions!`).
+    /// A plugin replaces the built-in participant with the same name.
+    #[clap(short, long)]
+    plugin_dir: Option<PathBuf>,
+    /// the directory containing the inputs, named e.g. `day01.txt.age` (or `day01.txt` with --plaintext)
+    #[clap(short, long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/inputs"))]
+    inputs: PathBuf,
+    /// the inputs are not encrypted
+    #[clap(long, conflicts_with = "identity")]
+    plaintext: bool,
+    /// an age identity file or unencrypted SSH private key to decrypt the inputs with,
+    /// instead of the passphrase in AGE_PASSPHRASE
+    #[clap(long)]
+    identity: Option<PathBuf>,
+    /// the manifest with the hashed answers of the inputs, encrypted like them if it ends in
+    /// `.age` [default: `answers.toml.age` in the inputs directory, or `answers.toml` with
+    /// --plaintext]
+    #[clap(long)]
+    answers: Option<PathBuf>,
+    /// a directory of unpublished inputs that are only verified, encrypted with the passphrase
+    /// in HELD_OUT_PASSPHRASE. Solutions that pass the public inputs of a day but fail a held-out
+    /// one are marked as suspicious
+    #[clap(long)]
+    held_out: Option<PathBuf>,
+    /// an age identity file or unencrypted SSH private key to decrypt the held-out inputs with
+    #[clap(long, requires = "held_out")]
+    held_out_identity: Option<PathBuf>,
+    /// the number of inputs per day to benchmark, the remaining inputs are only verified
+    #[clap(long, default_value_t = 1)]
+    bench_inputs: usize,
+    /// the number of fresh processes to measure the cold start of each benchmarked input in,
+    /// 0 disables cold runs
+    #[clap(long, default_value_t = 0)]
+    cold_runs: usize,
+    /// how often to run the calibration workload at the start, in the middle and at the end of
+    /// the run, to measure the speed of the machine. 0 disables the calibration
+    #[clap(long, default_value_t = 21)]
+    calibration_runs: usize,
+    /// flag a solution if its cold start is this many times slower than its warm median, which
+    /// hints at answers that are cached across benchmark iterations
+    #[clap(long, default_value_t = 1000.0)]
+    memoization_ratio: f64,
+    /// measure every sample in a fresh process instead of with criterion, so no state can
+    /// survive between samples. Much slower
+    #[clap(long)]
+    strict: bool,
+    /// also count the instructions, cycles, cache misses and branch misses of every phase of
+    /// the benchmarked inputs with Linux perf events. Skipped with a warning if the counters are
+    /// not available, e.g. in a container
+    #[clap(long)]
+    counters: bool,
+    /// also run the WASM programs in the plugin dir, built with `build-plugins --wasm`, and
+    /// record the fuel every phase of the benchmarked inputs consumes. Needs the wasm feature
+    #[clap(long, requires = "plugin_dir")]
+    wasm_fuel: bool,
+    /// abort a WASM run after it has consumed this much fuel
+    #[clap(long, default_value_t = 100_000_000_000)]
+    wasm_fuel_limit: u64,
+    /// run every verified input this many more times with fresh parses and check that the
+    /// answers don't change, 0 disables the check
+    #[clap(long, default_value_t = 0)]
+    determinism_runs: usize,
+    /// run the determinism checks in fresh processes, to also catch answers that depend on
+    /// per-process state like addresses
+    #[clap(long, requires = "determinism_runs")]
+    determinism_processes: bool,
+    /// also run every input that passed untrimmed, with CRLF line endings and with a trailing
+    /// blank line, and check that the answers don't change
+    #[clap(long)]
+    robustness: bool,
+    /// run the benchmarks on this CPU and keep the other threads of the harness off it. Best
+    /// combined with a CPU isolated from the scheduler, e.g. with `isolcpus`
+    #[clap(long, value_name = "CPU")]
+    pin_cpu: Option<usize>,
+    /// raise the priority of the benchmarks as far as allowed, which needs CAP_SYS_NICE
+    #[clap(long)]
+    high_priority: bool,
+    /// reuse the results of earlier runs stored in this directory for inputs whose solution,
+    /// input and harness settings didn't change, and store the new results there
+    #[clap(long)]
+    cache: Option<PathBuf>,
+    /// only run this part of the participants and days, e.g. `2/4` for the second of four
+    /// shards. The results of all shards can be combined by passing them all to the parser
+    #[clap(long, value_name = "INDEX/COUNT")]
+    shard: Option<Shard>,
+    /// the order to measure the participants and days in: `sequential`, `shuffled`, or
+    /// `interleaved` to measure all participants of a day back to back
+    #[clap(long, default_value = "sequential")]
+    order: Order,
+    /// the seed of the shuffled and interleaved orders, random if not given
+    #[clap(long)]
+    seed: Option<u64>,
+    /// the file to write the results to, one JSON object per line
+    #[clap(short, long, default_value = "results.jsonl")]
+    results: PathBuf,
+    /// the directory for the criterion output
+    #[clap(long, default_value = "target/criterion")]
+    criterion_dir: PathBuf,
+    /// a TOML config file, see the README for the format
+    #[clap(long)]
+    config: Option<PathBuf>,
+    /// override a time limit in seconds, for all days or a single day, e.g. `part2=60`,
+    /// `12:part1=20` or `all=1`. Can be given multiple times
+    #[clap(long = "timeout", value_name = "[DAY:]PHASE=SECONDS")]
+    timeouts: Vec<TimeoutArg>,
+    #[command(flatten)]
+    criterion: CriterionArgs,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build every participant as a separate plugin, recording the diagnostics of failed builds
+    BuildPlugins {
+        /// the directory to put the plugins and build failure records into
+        #[clap(short, long)]
+        out_dir: PathBuf,
+        /// the directory for the generated plugin crates and their build artifacts
+        #[clap(short, long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/../target/participants"))]
+        work_dir: PathBuf,
+        /// the participant registry
+        #[clap(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/../participants.toml"))]
+        participants: PathBuf,
+        /// also build every participant as a WASM program for --wasm-fuel, which needs the
+        /// wasm32-wasip1 target
+        #[clap(long)]
+        wasm: bool,
+    },
+    /// Add an input and its answers, or update an existing one. The input is encrypted with
+    /// AGE_PASSPHRASE, unless --plaintext is given
+    AddInput {
+        /// the day of the input
+        #[clap(short, long)]
+        day: u8,
+        /// the name of the input, `dayXX` by default. Additional inputs of a day are named
+        /// e.g. `day01-franco`
+        #[clap(short, long)]
+        name: Option<String>,
+        /// the unencrypted input file, only the answers are updated if it is omitted
+        #[clap(short, long)]
+        input: Option<PathBuf>,
+        /// the answer of part 1
+        #[clap(long)]
+        part1: String,
+        /// the answer of part 2
+        #[clap(long)]
+        part2: String,
+    },
+    /// Feed mutations of the inputs to every day that solves them, each in a fresh process, and
+    /// keep the inputs that make a solution crash or hang
+    Fuzz {
+        /// only fuzz this participant
+        #[clap(short, long)]
+        user: Option<String>,
+        /// only fuzz this day
+        #[clap(short, long)]
+        day: Option<u8>,
+        /// the number of mutated inputs per participant and day
+        #[clap(short = 'n', long, default_value_t = 200)]
+        iterations: usize,
+        /// the seed of the mutations, random by default
+        #[clap(long)]
+        seed: Option<u64>,
+        /// the time in seconds after which a run counts as a hang
+        #[clap(long, default_value_t = 1.0)]
+        time_limit: f64,
+        /// the directory to store the crashing and hanging inputs in
+        #[clap(long, default_value = "fuzz-corpus")]
+        corpus_dir: PathBuf,
+    },
+    /// Run all participants on inputs without known answers and report where they disagree,
+    /// taking the majority answer as the right one
+    Differential {
+        /// a directory of plaintext inputs named like `day05-anything.txt`
+        #[clap(
+            long,
+            required_unless_present = "generator",
+            conflicts_with = "generator"
+        )]
+        dir: Option<PathBuf>,
+        /// a program called as `<program> <day> <seed>` that prints an input
+        #[clap(long)]
+        generator: Option<PathBuf>,
+        /// the number of generated inputs per day
+        #[clap(short = 'n', long, default_value_t = 10, requires = "generator")]
+        count: usize,
+        /// the seed of the first generated input, random by default
+        #[clap(long, requires = "generator")]
+        seed: Option<u64>,
+        /// only test this day
+        #[clap(short, long)]
+        day: Option<u8>,
+    },
+    /// Check the answers and time limits of every day without benchmarking, running the days in
+    /// parallel on all cores, and print a pass/fail grid. Fails if any check fails
+    Verify {
+        /// only check this participant
+        #[clap(short, long)]
+        user: Option<String>,
+        /// only check this day
+        #[clap(short, long)]
+        day: Option<u8>,
+    },
+    /// Run a day once on the input from stdin and print the answers and the elapsed time, used
+    /// for the cold-start measurements and determinism checks
+    #[command(hide = true)]
+    RunOnce {
+        #[clap(long)]
+        user: String,
+        #[clap(long)]
+        day: u8,
+    },
+}
+
+#[derive(Clone)]
+pub enum ExecutionError {
     Timeout,
-    WrongAnswer,
+    /// the wrong answer
+    WrongAnswer(String),
     NotImplemented,
-    Panic,
+    /// the panic message
+    Panic(String),
+    /// how a repeated run differed from the verified one
+    NonDeterministic(String),
+    /// how a held-out input failed, while the public inputs passed
+    Suspicious(String),
+}
+
+impl ExecutionError {
+    fn status(&self) -> Status {
+        match self {
+            ExecutionError::Timeout => Status::Timeout,
+            ExecutionError::WrongAnswer(_) => Status::WrongAnswer,
+            ExecutionError::NotImplemented => Status::NotImplemented,
+            ExecutionError::Panic(_) => Status::Panicked,
+            ExecutionError::NonDeterministic(_) => Status::NonDeterministic,
+            ExecutionError::Suspicious(_) => Status::Suspicious,
+        }
+    }
+
+    fn details(&self) -> Option<String> {
+        match self {
+            ExecutionError::WrongAnswer(answer) => Some(format!("wrong answer {answer:?}")),
+            ExecutionError::Panic(msg)
+            | ExecutionError::NonDeterministic(msg)
+            | ExecutionError::Suspicious(msg) => Some(msg.clone()),
+            ExecutionError::Timeout | ExecutionError::NotImplemented => None,
+        }
+    }
+}
+
+fn panic_message(e: &(dyn Any + Send)) -> String {
+    if let Some(msg) = e.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = e.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Turns the payload of a caught panic into an [`ExecutionError`].
+fn panic_error(e: Box<dyn Any + Send>) -> ExecutionError {
+    let msg = panic_message(e.as_ref());
+    if msg.contains("not yet implemented") {
+        ExecutionError::NotImplemented
+    } else {
+        ExecutionError::Panic(msg)
+    }
+}
+
+/// Settings that apply to all benchmarks.
+pub struct BenchOptions {
+    pub criterion_dir: PathBuf,
+    pub timeouts: Timeouts,
+    pub criterion: CriterionSettings,
+    /// the number of inputs per day to benchmark
+    pub bench_inputs: usize,
+    /// the part of the participants and days to run
+    pub shard: Option<Shard>,
+    /// the number of cold starts to measure, 0 if disabled
+    pub cold_runs: usize,
+    /// the ratio of the cold start to the warm median above which memoization is suspected
+    pub memoization_ratio: f64,
+    /// measure every sample in a fresh process
+    pub strict: bool,
+    /// count the hardware events, if enabled and available
+    pub counters: bool,
+    /// the WASM programs to measure the fuel of, if enabled
+    pub wasm: Option<wasm::Runtime>,
+    /// the number of repeated runs of the determinism check, 0 if disabled
+    pub determinism_runs: usize,
+    /// run the determinism checks in fresh processes
+    pub determinism_processes: bool,
+    /// check the answers on variants of the inputs
+    pub robustness: bool,
+    /// passed on to the run-once processes, so they load the same solutions
+    pub plugin_dir: Option<PathBuf>,
+}
+
+impl BenchOptions {
+    /// The time limit of a phase, `None` for [`Phase::Total`].
+    fn timeout(&self, day: u8, phase: Phase) -> Option<Duration> {
+        self.timeouts.get(day, phase).map(Duration::from_secs_f64)
+    }
+
+    /// The time limit of a single run that parses and solves both parts.
+    fn run_timeout(&self, day: u8) -> Duration {
+        [Phase::Parse, Phase::Part1, Phase::Part2]
+            .into_iter()
+            .filter_map(|phase| self.timeout(day, phase))
+            .sum()
+    }
+
+    /// The criterion instance for a phase whose verification run took `duration`.
+    fn criterion(&self, duration: Duration) -> Criterion {
+        Criterion::default()
+            .output_directory(&self.criterion_dir)
+            .warm_up_time(Duration::from_secs_f64(self.criterion.warm_up))
+            .measurement_time(Duration::from_secs_f64(self.criterion.measurement_time))
+            .sample_size(self.criterion.sample_size(duration))
+            .without_plots()
+    }
 }
 
-fn bench_aoc_day<S: AdventOfCodeDay + 'static>(
+/// One of the inputs of a day, together with its expected answers.
+pub struct TestCase<'a> {
+    pub day: u8,
+    /// the name of the input, e.g. `day01` or `day01-franco`
+    pub name: &'a str,
+    pub input: &'a str,
+    pub answers: &'a ExpectedAnswers,
+}
+
+/// Verifies the solution on a single input and, if `benchmark` is set, benchmarks it.
+fn bench_aoc_day<S: DaySolver>(
+    solver: S,
     username: &str,
-    day: u8,
-    input: &'static [u8],
-    expected_stage1: &'static str,
-    expected_stage2: &'static str,
+    case: &TestCase,
+    options: &BenchOptions,
+    benchmark: bool,
 ) -> (
     Result<(), ExecutionError>,
     Result<(), ExecutionError>,
     Result<(), ExecutionError>,
 ) {
-    let key = std::env::var("AGE_PASSPHRASE")
-        .expect("need AGE_PASSPHRASE to be set")
-        .into();
-    let identity = age::scrypt::Identity::new(key);
-    let input_dec = String::from_utf8(age::decrypt(&identity, &input).expect("can decrypt input"))
-        .expect("input is utf8");
-    println!("Benchmarking user {}, day{:02}", username, day);
-    if core::any::TypeId::of::<S>() == core::any::TypeId::of::<()>() {
+    let input_dec = case.input.to_string();
+    let name = case.name;
+    if !solver.is_implemented() {
         return (
             Err(ExecutionError::NotImplemented),
             Err(ExecutionError::NotImplemented),
             Err(ExecutionError::NotImplemented),
         );
     }
-    // give the user's code 60 seconds to run
-
-    // check if the parser is implemented and takes less than 1 second
+    let start = Instant::now();
+    // check if the parser is implemented and finishes within the time limit
     let (sender, receiver) = mpsc::channel();
     let input = input_dec.clone();
-    let t = thread::spawn(move || {
+    let s = solver.clone();
+    let t = isolation::spawn(move || {
         let res = panic::catch_unwind(move || {
             let input = input.trim();
-            let _parsed_input = S::parse_input(input);
+            let _parsed_input = s.parse(input);
             ()
         });
         let _ = sender.send(res);
     });
-    let parse_result = match receiver.recv_timeout(Duration::from_secs(1)) {
+    let parse_result = match recv_within(&receiver, options.timeout(case.day, Phase::Parse)) {
         Ok(Ok(())) => Ok(()),
-        Ok(Err(_)) => Err(ExecutionError::Panic),
+        Ok(Err(e)) => Err(ExecutionError::Panic(panic_message(e.as_ref()))),
         Err(_) => Err(ExecutionError::Timeout),
     };
+    let dur_parse = start.elapsed();
     // if the parser timed out, we can't run the other benchmarks
     if let Err(ExecutionError::Timeout) = parse_result {
         return (
@@ -65,402 +432,826 @@ fn bench_aoc_day<S: AdventOfCodeDay + 'static>(
             Err(ExecutionError::Timeout),
             Err(ExecutionError::Timeout),
         );
-    } else if let Err(ExecutionError::Panic) = parse_result {
+    } else if let Err(e @ ExecutionError::Panic(_)) = parse_result {
         // if the parser panicked, we can't run the other benchmarks
-        return (
-            Err(ExecutionError::Panic),
-            Err(ExecutionError::Panic),
-            Err(ExecutionError::Panic),
-        );
+        return (Err(e.clone()), Err(e.clone()), Err(e));
     }
     let _ = t.join();
 
-    let mut c = Criterion::default()
-        .sample_size(100)
-        .warm_up_time(Duration::from_secs(1))
-        .measurement_time(Duration::from_secs(1))
-        .without_plots();
-    let input = input_dec.clone();
-    c.bench_function(&format!("{username}-day{day:02}-parse"), |b| {
-        let trimmed_input = input.trim();
-        b.iter(move || {
-            black_box(S::parse_input(black_box(trimmed_input)));
-        })
-    });
+    if benchmark {
+        let mut c = options.criterion(dur_parse);
+        let input = input_dec.clone();
+        c.bench_function(&format!("{username}-{name}-{}", Phase::Parse), |b| {
+            let trimmed_input = input.trim();
+            let solver = &solver;
+            b.iter(move || {
+                black_box(solver.parse(black_box(trimmed_input)));
+            })
+        });
+    }
 
     let start = Instant::now();
-    // check if part1 is implemented and takes less than 10 second
+    // check if part1 is implemented and finishes within the time limit
     let (sender, receiver) = mpsc::channel();
     let input = input_dec.clone();
-    let t = thread::spawn(move || {
+    let s = solver.clone();
+    let answers = case.answers.clone();
+    let t = isolation::spawn(move || {
         let res = panic::catch_unwind(|| {
             let input = input.trim();
-            let parsed_input = S::parse_input(input);
-            let stage1 = S::solve_part1(black_box(&parsed_input));
-            if stage1.to_string() != expected_stage1 {
-                return Err(ExecutionError::WrongAnswer);
+            let parsed_input = s.parse(input);
+            let stage1 = s.part1(black_box(&parsed_input));
+            let stage1 = stage1.to_string();
+            if !answers.check_part1(&stage1) {
+                return Err(ExecutionError::WrongAnswer(stage1));
             }
             Ok(())
         });
         let _ = sender.send(res);
     });
-    let part1_result = match receiver.recv_timeout(Duration::from_secs(10)) {
+    let part1_result = match recv_within(&receiver, options.timeout(case.day, Phase::Part1)) {
         Ok(Ok(x)) => x,
-        Ok(Err(e)) => {
-            if let Some(msg) = e.downcast_ref::<&str>() {
-                if msg.contains("not yet implemented") {
-                    Err(ExecutionError::NotImplemented)
-                } else {
-                    Err(ExecutionError::Panic)
-                }
-            } else {
-                Err(ExecutionError::Panic)
-            }
-        }
+        Ok(Err(e)) => Err(panic_error(e)),
         Err(_) => Err(ExecutionError::Timeout),
     };
     let dur_part1 = start.elapsed();
     if matches!(part1_result, Ok(())) {
         let _ = t.join();
-
-        let mut c = Criterion::default()
-            .warm_up_time(Duration::from_secs(1))
-            .measurement_time(Duration::from_secs(1))
-            .without_plots();
-        c = if dur_part1 > Duration::from_millis(100) && dur_part1 < Duration::from_secs(1) {
-            c.sample_size(50)
-        } else if dur_part1 > Duration::from_secs(1) {
-            c.sample_size(10)
-        } else {
-            c.sample_size(100)
-        };
+    }
+    if matches!(part1_result, Ok(())) && benchmark {
+        let mut c = options.criterion(dur_part1);
 
         let input = input_dec.clone();
-        c.bench_function(&format!("{username}-day{day:02}-part1"), |b| {
+        c.bench_function(&format!("{username}-{name}-{}", Phase::Part1), |b| {
             let trimmed_input = input.trim();
             b.iter_batched_ref(
                 || {
-                    let parsed_input = S::parse_input(black_box(trimmed_input));
+                    let parsed_input = solver.parse(black_box(trimmed_input));
                     parsed_input
                 },
-                |parsed_input| {
-                    black_box(S::solve_part1(parsed_input));
-                },
+                |parsed_input| solver.bench_part1(parsed_input),
                 BatchSize::LargeInput,
             )
         });
     }
     let start = Instant::now();
-    // check if part2 is implemented and takes less than 30 second
+    // check if part2 is implemented and finishes within the time limit
     let (sender, receiver) = mpsc::channel();
     let input = input_dec.clone();
-    let t = thread::spawn(move || {
+    let s = solver.clone();
+    let answers = case.answers.clone();
+    let t = isolation::spawn(move || {
         let res = panic::catch_unwind(|| {
             let input = input.trim();
-            let parsed_input = S::parse_input(input);
+            let parsed_input = s.parse(input);
             // also re-do part1, since it might change the input
-            let _stage1 = S::solve_part1(black_box(&parsed_input));
-            let stage2 = S::solve_part2(black_box(&parsed_input));
-            if stage2.to_string() != expected_stage2 {
-                return Err(ExecutionError::WrongAnswer);
+            let _stage1 = s.part1(black_box(&parsed_input));
+            let stage2 = s.part2(black_box(&parsed_input));
+            let stage2 = stage2.to_string();
+            if !answers.check_part2(&stage2) {
+                return Err(ExecutionError::WrongAnswer(stage2));
             }
             Ok(())
         });
         let _ = sender.send(res);
     });
-    let part2_result = match receiver.recv_timeout(Duration::from_secs(30)) {
+    let part2_result = match recv_within(&receiver, options.timeout(case.day, Phase::Part2)) {
         Ok(Ok(x)) => x,
-        Ok(Err(e)) => {
-            if let Some(msg) = e.downcast_ref::<&str>() {
-                println!("msg: {}", msg);
-                if msg.contains("not yet implemented") {
-                    Err(ExecutionError::NotImplemented)
-                } else {
-                    Err(ExecutionError::Panic)
-                }
-            } else {
-                Err(ExecutionError::Panic)
-            }
-        }
+        Ok(Err(e)) => Err(panic_error(e)),
         Err(_) => Err(ExecutionError::Timeout),
     };
     let dur_part2 = start.elapsed();
     if matches!(part2_result, Ok(())) {
         let _ = t.join();
-        let mut c = Criterion::default()
-            .warm_up_time(Duration::from_secs(1))
-            .measurement_time(Duration::from_secs(1))
-            .without_plots();
-        c = if dur_part2 > Duration::from_millis(100) && dur_part2 < Duration::from_secs(1) {
-            c.sample_size(50)
-        } else if dur_part2 > Duration::from_secs(1) {
-            c.sample_size(10)
-        } else {
-            c.sample_size(100)
-        };
+    }
+    if matches!(part2_result, Ok(())) && benchmark {
+        let mut c = options.criterion(dur_part2);
         let input = input_dec.clone();
-        c.bench_function(&format!("{username}-day{day:02}-part2"), |b| {
+        c.bench_function(&format!("{username}-{name}-{}", Phase::Part2), |b| {
             let trimmed_input = input.trim();
             b.iter_batched_ref(
                 || {
-                    let parsed_input = S::parse_input(black_box(trimmed_input));
-                    let _stage1 = S::solve_part1(&parsed_input);
+                    let parsed_input = solver.parse(black_box(trimmed_input));
+                    let _stage1 = solver.part1(&parsed_input);
                     parsed_input
                 },
-                |parsed_input| {
-                    black_box(S::solve_part2(parsed_input));
-                },
+                |parsed_input| solver.bench_part2(parsed_input),
                 criterion::BatchSize::LargeInput,
             )
         });
-        c.bench_function(&format!("{username}-day{day:02}-Total"), |b| {
+        c.bench_function(&format!("{username}-{name}-{}", Phase::Total), |b| {
             let trimmed_input = input.trim();
             b.iter(|| {
-                let parsed_input = S::parse_input(trimmed_input);
-                (S::solve_part1(&parsed_input), S::solve_part2(&parsed_input));
+                let parsed_input = solver.parse(trimmed_input);
+                solver.bench_part1(&parsed_input);
+                solver.bench_part2(&parsed_input);
             })
         });
     }
     (parse_result, part1_result, part2_result)
 }
 
-fn bench_aoc<S: AdventOfCodeSolutions + 'static>(username: &str) {
-    for (day, input, out1, out2) in INPUTS_OUTPUTS {
-        let result = match day {
-            1 => bench_aoc_day::<S::Day01>(username, day, input, out1, out2),
-            2 => bench_aoc_day::<S::Day02>(username, day, input, out1, out2),
-            3 => bench_aoc_day::<S::Day03>(username, day, input, out1, out2),
-            4 => bench_aoc_day::<S::Day04>(username, day, input, out1, out2),
-            5 => bench_aoc_day::<S::Day05>(username, day, input, out1, out2),
-            6 => bench_aoc_day::<S::Day06>(username, day, input, out1, out2),
-            7 => bench_aoc_day::<S::Day07>(username, day, input, out1, out2),
-            8 => bench_aoc_day::<S::Day08>(username, day, input, out1, out2),
-            9 => bench_aoc_day::<S::Day09>(username, day, input, out1, out2),
-            10 => bench_aoc_day::<S::Day10>(username, day, input, out1, out2),
-            11 => bench_aoc_day::<S::Day11>(username, day, input, out1, out2),
-            12 => bench_aoc_day::<S::Day12>(username, day, input, out1, out2),
-            13 => bench_aoc_day::<S::Day13>(username, day, input, out1, out2),
-            14 => bench_aoc_day::<S::Day14>(username, day, input, out1, out2),
-            15 => bench_aoc_day::<S::Day15>(username, day, input, out1, out2),
-            16 => bench_aoc_day::<S::Day16>(username, day, input, out1, out2),
-            17 => bench_aoc_day::<S::Day17>(username, day, input, out1, out2),
-            18 => bench_aoc_day::<S::Day18>(username, day, input, out1, out2),
-            19 => bench_aoc_day::<S::Day19>(username, day, input, out1, out2),
-            20 => bench_aoc_day::<S::Day20>(username, day, input, out1, out2),
-            21 => bench_aoc_day::<S::Day21>(username, day, input, out1, out2),
-            22 => bench_aoc_day::<S::Day22>(username, day, input, out1, out2),
-            23 => bench_aoc_day::<S::Day23>(username, day, input, out1, out2),
-            24 => bench_aoc_day::<S::Day24>(username, day, input, out1, out2),
-            25 => bench_aoc_day::<S::Day25>(username, day, input, out1, out2),
-            _ => unreachable!(),
+/// Parses the input as is and solves both parts in another thread, giving up after `timeout`.
+fn solve<S: DaySolver>(
+    solver: S,
+    input: &str,
+    timeout: Duration,
+) -> Result<(String, String), ExecutionError> {
+    let (sender, receiver) = mpsc::channel();
+    let input = input.to_string();
+    isolation::spawn(move || {
+        let res = panic::catch_unwind(move || {
+            let parsed = solver.parse(&input);
+            let part1 = solver.part1(&parsed).to_string();
+            let part2 = solver.part2(&parsed).to_string();
+            (part1, part2)
+        });
+        let _ = sender.send(res);
+    });
+    match receiver.recv_timeout(timeout) {
+        Ok(Ok(answers)) => Ok(answers),
+        Ok(Err(e)) => Err(panic_error(e)),
+        Err(_) => Err(ExecutionError::Timeout),
+    }
+}
+
+/// Waits for the result of a phase for at most `timeout`, or as long as it takes without one.
+fn recv_within<T>(
+    receiver: &mpsc::Receiver<T>,
+    timeout: Option<Duration>,
+) -> Result<T, mpsc::RecvTimeoutError> {
+    match timeout {
+        Some(timeout) => receiver.recv_timeout(timeout),
+        None => receiver
+            .recv()
+            .map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+    }
+}
+
+/// The inputs of a day with their answers and whether they are held out.
+type DayCases<'a> = Vec<(&'a dyn InputProvider, &'a ExpectedAnswers, bool)>;
+
+/// The inputs of every day, the held-out inputs of a day after the public ones.
+fn test_cases<'a>(
+    inputs: &'a dyn InputProvider,
+    answers: &'a Answers,
+    held_out: Option<&'a HeldOut>,
+) -> BTreeMap<u8, DayCases<'a>> {
+    let mut cases: BTreeMap<u8, DayCases> = BTreeMap::new();
+    for answers in &answers.inputs {
+        cases
+            .entry(answers.day)
+            .or_default()
+            .push((inputs, answers, false));
+    }
+    if let Some(held_out) = held_out {
+        for answers in &held_out.answers.inputs {
+            cases
+                .entry(answers.day)
+                .or_default()
+                .push((&held_out.inputs, answers, true));
+        }
+    }
+    cases
+}
+
+/// Verifies every input of a day and benchmarks the first `bench_inputs` of them. The held-out
+/// inputs are verified after the public ones.
+fn bench_day(
+    participant: &Participant,
+    cases: &DayCases,
+    options: &BenchOptions,
+    cache: Option<&Cache>,
+    calibration_ns: Option<f64>,
+    results: &mut ResultsWriter,
+) -> Result<()> {
+    let username = participant.name.as_str();
+    let mut benchmarked = 0;
+    // whether a phase passed all public inputs of the day
+    let mut public_passed: HashMap<(u8, Phase), bool> = HashMap::new();
+    for &(inputs, answers, is_held_out) in cases {
+        let (day, name) = (answers.day, answers.name.as_str());
+        let input = inputs.input(name)?;
+        let case = TestCase {
+            day,
+            name,
+            input: &input,
+            answers,
         };
-        if let Err(e) = &result.0 {
-            print!("{username}-day{day:02}-parse: ");
-
-            match e {
-                ExecutionError::Timeout => println!("timeout"),
-                ExecutionError::WrongAnswer => println!("wrong answer"),
-                ExecutionError::NotImplemented => println!("not implemented"),
-                ExecutionError::Panic => println!("panicked"),
-            }
+        let benchmark = !is_held_out && benchmarked < options.bench_inputs;
+        if benchmark {
+            benchmarked += 1;
         }
-        if let Err(e) = &result.1 {
-            print!("{username}-day{day:02}-part1: ");
-
-            match e {
-                ExecutionError::Timeout => println!("timeout"),
-                ExecutionError::WrongAnswer => println!("wrong answer"),
-                ExecutionError::NotImplemented => println!("not implemented"),
-                ExecutionError::Panic => println!("panicked"),
+        let events = match cache
+            .map(|c| c.load(username, &case, benchmark))
+            .transpose()?
+        {
+            Some(Some(mut events)) => {
+                println!("Reusing the cached results of user {username}, {name}");
+                for event in &mut events {
+                    if let Event::Phase(result) = event {
+                        result.cached = true;
+                    }
+                }
+                events
+            }
+            _ => {
+                let events = bench_case(
+                    participant,
+                    &case,
+                    options,
+                    benchmark,
+                    is_held_out,
+                    &public_passed,
+                )?;
+                if let Some(cache) = cache {
+                    cache.store(username, &case, benchmark, &events, calibration_ns)?;
+                }
+                events
             }
+        };
+        for event in &events {
+            if let Event::Phase(result) = event {
+                if !is_held_out && result.phase != Phase::Total {
+                    *public_passed.entry((day, result.phase)).or_insert(true) &=
+                        result.status == Status::Ok;
+                }
+            }
+            results.write(event)?;
         }
-        if let Err(e) = &result.2 {
-            print!("{username}-day{day:02}-part2: ");
-
-            match e {
-                ExecutionError::Timeout => println!("timeout"),
-                ExecutionError::WrongAnswer => println!("wrong answer"),
-                ExecutionError::NotImplemented => println!("not implemented"),
-                ExecutionError::Panic => println!("panicked"),
+    }
+    Ok(())
+}
+
+/// Verifies and, if `benchmark` is set, measures a single input, returning the results.
+fn bench_case(
+    participant: &Participant,
+    case: &TestCase,
+    options: &BenchOptions,
+    benchmark: bool,
+    is_held_out: bool,
+    public_passed: &HashMap<(u8, Phase), bool>,
+) -> Result<Vec<Event>> {
+    let username = participant.name.as_str();
+    let (day, name, input) = (case.day, case.name, case.input);
+    let mut events = Vec::new();
+    if benchmark {
+        println!("Benchmarking user {}, {}", username, name);
+    } else {
+        println!("Verifying user {}, {}", username, name);
+    }
+    // in strict mode the samples are taken in fresh processes below, instead of by criterion
+    let (mut parse, mut part1, mut part2) =
+        participant
+            .day(day)
+            .bench(username, case, options, benchmark && !options.strict);
+    if options.determinism_runs > 0 && parse.is_ok() && part1.is_ok() && part2.is_ok() {
+        println!("Checking determinism of user {username}, {name}");
+        (part1, part2) = determinism::check(participant.day(day), username, case, options);
+    }
+    for (phase, result) in [
+        (Phase::Parse, &mut parse),
+        (Phase::Part1, &mut part1),
+        (Phase::Part2, &mut part2),
+    ] {
+        if is_held_out && public_passed.get(&(day, phase)) == Some(&true) {
+            // a timeout can be an honest slow solution on a harder input
+            if let Err(e @ (ExecutionError::WrongAnswer(_) | ExecutionError::Panic(_))) = result {
+                let details = e.details().unwrap_or_default();
+                *result = Err(ExecutionError::Suspicious(format!(
+                    "passes the public inputs, but not this held-out one: {details}"
+                )));
             }
         }
+    }
+    let total = match (&parse, &part1, &part2) {
+        (Ok(()), Ok(()), Ok(())) => Status::Ok,
+        (
+            Err(ExecutionError::NotImplemented),
+            Err(ExecutionError::NotImplemented),
+            Err(ExecutionError::NotImplemented),
+        ) => Status::NotImplemented,
+        // a held-out failure is shown as such in the total as well, not as a plain error
+        _ if [&parse, &part1, &part2]
+            .iter()
+            .any(|r| matches!(r, Err(ExecutionError::Suspicious(_)))) =>
+        {
+            Status::Suspicious
+        }
+        _ => Status::Error,
+    };
+    let mut outcomes = Vec::new();
+    for (phase, result) in [
+        (Phase::Parse, &parse),
+        (Phase::Part1, &part1),
+        (Phase::Part2, &part2),
+    ] {
         match result {
-            (Ok(()), Ok(()), Ok(())) => {}
-            (
-                Err(ExecutionError::NotImplemented),
-                Err(ExecutionError::NotImplemented),
-                Err(ExecutionError::NotImplemented),
-            ) => {
-                println!("{username}-day{day:02}-Total: not implemented");
+            Ok(()) => outcomes.push((phase, Status::Ok, None, benchmark)),
+            Err(e) => {
+                println!("{username}-{name}-{phase}: {}", e.status());
+                outcomes.push((phase, e.status(), e.details(), false));
             }
-            _ => {
-                println!("{username}-day{day:02}-Total: error");
+        }
+    }
+    // the Total benchmark runs whenever part2 succeeded
+    outcomes.push((Phase::Total, total, None, benchmark && part2.is_ok()));
+    let cold = if benchmark && total == Status::Ok && options.cold_runs > 0 {
+        println!("Measuring cold starts of user {username}, {name}");
+        match cold::measure_runs(
+            options.plugin_dir.as_deref(),
+            username,
+            day,
+            input,
+            options.cold_runs,
+            options.run_timeout(day),
+        ) {
+            Ok(cold) => Some(cold),
+            Err(e) => {
+                println!("{username}-{name}-cold: {e}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let strict = if benchmark && total == Status::Ok && options.strict {
+        println!("Benchmarking user {username}, {name} in fresh processes");
+        match cold::measure_strict(
+            options.plugin_dir.as_deref(),
+            username,
+            day,
+            input,
+            options.criterion.sample_size,
+            options.run_timeout(day),
+        ) {
+            Ok(timings) => Some(timings),
+            Err(e) => {
+                println!("{username}-{name}-strict: {e}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    // opened right before counting, so only the threads the solution spawns from here on are
+    // counted, and not those left over from earlier runs
+    let counters = if options.counters && benchmark && total == Status::Ok {
+        println!("Counting hardware events of user {username}, {name}");
+        match Counters::open() {
+            Ok(counters) => participant.day(day).count(input, &counters),
+            Err(e) => {
+                println!("{username}-{name}-counters: {e}");
+                BTreeMap::new()
+            }
+        }
+    } else {
+        BTreeMap::new()
+    };
+    let fuel = match &options.wasm {
+        Some(wasm) if benchmark && total == Status::Ok => {
+            println!("Measuring the WASM fuel of user {username}, {name}");
+            match wasm.fuel(username, day, input, case.answers) {
+                Ok(fuel) => fuel,
+                Err(e) => {
+                    println!("{username}-{name}-wasm: {e}");
+                    BTreeMap::new()
+                }
             }
         }
+        _ => BTreeMap::new(),
+    };
+    for (phase, status, error, benchmarked) in outcomes {
+        let timing = if !benchmarked {
+            None
+        } else if options.strict {
+            strict.as_ref().and_then(|t| t.get(&phase).cloned())
+        } else {
+            let id = format!("{username}-{name}-{phase}");
+            Some(results::read_timing(&options.criterion_dir, &id)?)
+        };
+        let cold = if phase == Phase::Total {
+            cold.clone()
+        } else {
+            None
+        };
+        // a cached answer makes the warm iterations a lookup, while the first call in a
+        // fresh process still has to do the work
+        let suspected_memoization = match (&cold, &timing) {
+            (Some(cold), Some(timing))
+                if cold.median_ns / timing.median_ns > options.memoization_ratio =>
+            {
+                let ratio = cold.median_ns / timing.median_ns;
+                println!(
+                    "{username}-{name}-{phase}: the cold start is {ratio:.0} times slower \
+                     than the warm median, are answers cached across iterations?"
+                );
+                Some(ratio)
+            }
+            _ => None,
+        };
+        events.push(Event::Phase(PhaseResult {
+            user: username.to_string(),
+            day,
+            input: name.to_string(),
+            phase,
+            status,
+            timing,
+            cold,
+            suspected_memoization,
+            counters: counters.get(&phase).cloned(),
+            fuel: fuel.get(&phase).copied(),
+            cached: false,
+            calibration_ns: None,
+            error,
+        }));
     }
+    if total != Status::Ok {
+        println!("{username}-{name}-{}: {total}", Phase::Total);
+    }
+    if options.robustness && total == Status::Ok {
+        println!("Checking robustness of user {username}, {name}");
+        for result in robustness::check(participant.day(day), username, case, options) {
+            if let Some(error) = &result.error {
+                println!(
+                    "{username}-{name}-robustness: {} input: {error}",
+                    result.variant
+                );
+            }
+            events.push(Event::Robustness(result));
+        }
+    }
+    Ok(events)
 }
 
-fn benches() {
-    bench_aoc::<dkales_aoc::AoC2024>("dkales");
-    bench_aoc::<franco_aoc::AoC2024>("franco");
-    bench_aoc::<fabian_aoc::AoC2024>("fabian1409");
-    bench_aoc::<simon_aoc::AoC2024>("devise");
+// the `participants` and `static_revisions` functions, generated by build.rs from
+// participants.toml
+include!(concat!(env!("OUT_DIR"), "/participants.rs"));
+
+/// The participants to benchmark, with the plugins in `plugin_dir` replacing the built-in ones,
+/// and the names of those whose plugin failed to build.
+fn load_participants(plugin_dir: Option<&Path>) -> Result<(Vec<Participant>, Vec<String>)> {
+    let mut participants = participants();
+    let mut failed = Vec::new();
+    if let Some(plugin_dir) = plugin_dir {
+        for name in builder::failed_builds(plugin_dir)? {
+            participants.retain(|p| p.name != name);
+            failed.push(name);
+        }
+        for plugin in plugin::load_dir(plugin_dir)? {
+            participants.retain(|p| p.name != plugin.name);
+            participants.push(plugin);
+        }
+    }
+    Ok((participants, failed))
 }
 
-fn main() {
-    benches();
-    Criterion::default().final_summary();
+fn add_input(
+    args: &Args,
+    day: u8,
+    name: Option<String>,
+    input: Option<&Path>,
+    part1: &str,
+    part2: &str,
+) -> Result<()> {
+    if !(1..=25).contains(&day) {
+        return Err(eyre!("day must be between 1 and 25"));
+    }
+    let name = name.unwrap_or_else(|| match args.held_out {
+        Some(_) => format!("day{day:02}-held-out"),
+        None => format!("day{day:02}"),
+    });
+    if !name.starts_with(&format!("day{day:02}")) || name.contains(char::is_whitespace) {
+        return Err(eyre!(
+            "the name of an input of day {day} has to start with day{day:02}"
+        ));
+    }
+    if let Some(dir) = &args.held_out {
+        let content = match input {
+            Some(input) => Some(
+                std::fs::read_to_string(input)
+                    .with_context(|| format!("cannot read {}", input.display()))?,
+            ),
+            None => None,
+        };
+        let answers = ExpectedAnswers::new(day, &name, part1, part2);
+        if let Some(path) = held_out::add(dir, answers, content.as_deref())? {
+            println!("Stored held-out input {name} in {}", path.display());
+        }
+        println!(
+            "Updated the answers of held-out input {name} in {}",
+            dir.display()
+        );
+        return Ok(());
+    }
+    let path = answers_path(args);
+    let unencrypted = unencrypted_answers(&path);
+    let mut answers = if path.exists() || unencrypted.is_some() {
+        load_answers(args)?
+    } else {
+        Answers::default()
+    };
+    if let Some(input) = input {
+        let content = std::fs::read_to_string(input)
+            .with_context(|| format!("cannot read {}", input.display()))?;
+        let path = inputs::store(&args.inputs, &name, &content, args.plaintext)?;
+        println!("Stored input {name} in {}", path.display());
+    } else if ["txt", "txt.age"]
+        .iter()
+        .all(|ext| !args.inputs.join(format!("{name}.{ext}")).exists())
+    {
+        println!("Warning: there is no input {name} yet, add it with --input");
+    }
+    answers.upsert(ExpectedAnswers::new(day, &name, part1, part2));
+    if is_encrypted(&path) {
+        answers.save_encrypted(&path, inputs::passphrase_from_env()?)?;
+    } else {
+        answers.save(&path)?;
+    }
+    println!("Updated the answers of {name} in {}", path.display());
+    if let Some(unencrypted) = unencrypted {
+        std::fs::remove_file(&unencrypted)
+            .with_context(|| format!("cannot remove {}", unencrypted.display()))?;
+        println!("Removed the unencrypted answers {}", unencrypted.display());
+    }
+    Ok(())
+}
+
+/// The path of the answers manifest, see [`Args::answers`].
+fn answers_path(args: &Args) -> PathBuf {
+    match (&args.answers, args.plaintext) {
+        (Some(path), _) => path.clone(),
+        (None, false) => args.inputs.join("answers.toml.age"),
+        (None, true) => args.inputs.join("answers.toml"),
+    }
+}
+
+fn is_encrypted(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "age")
 }
 
-const INPUTS_OUTPUTS: [(u8, &'static [u8], &'static str, &'static str); 25] = [
-    (
-        1,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day01.txt.age")),
-        "1319616",
-        "27267728",
-    ),
-    (
-        2,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day02.txt.age")),
-        "680",
-        "710",
-    ),
-    (
-        3,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day03.txt.age")),
-        "184511516",
-        "90044227",
-    ),
-    (
-        4,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day04.txt.age")),
-        "2427",
-        "1900",
-    ),
-    (
-        5,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day05.txt.age")),
-        "4957",
-        "6938",
-    ),
-    (
-        6,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day06.txt.age")),
-        "4964",
-        "1740",
-    ),
-    (
-        7,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day07.txt.age")),
-        "5702958180383",
-        "92612386119138",
-    ),
-    (
-        8,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day08.txt.age")),
-        "367",
-        "1285",
-    ),
-    (
-        9,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day09.txt.age")),
-        "6283404590840",
-        "6304576012713",
-    ),
-    (
-        10,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day10.txt.age")),
-        "746",
-        "1541",
-    ),
-    (
-        11,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day11.txt.age")),
-        "183248",
-        "218811774248729",
-    ),
-    (
-        12,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day12.txt.age")),
-        "1377008",
-        "815788",
-    ),
-    (
-        13,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day13.txt.age")),
-        "36758",
-        "76358113886726",
-    ),
-    (
-        14,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day14.txt.age")),
-        "231221760",
-        "6771",
-    ),
-    (
-        15,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day15.txt.age")),
-        "1559280",
-        "1576353",
-    ),
-    (
-        16,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day16.txt.age")),
-        "82460",
-        "590",
-    ),
-    (
-        17,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day17.txt.age")),
-        "5,1,3,4,3,7,2,1,7",
-        "216584205979245",
-    ),
-    (
-        18,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day18.txt.age")),
-        "326",
-        "18,62",
-    ),
-    (
-        19,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day19.txt.age")),
-        "255",
-        "621820080273474",
-    ),
-    (
-        20,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day20.txt.age")),
-        "1402",
-        "1020244",
-    ),
-    (
-        21,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day21.txt.age")),
-        "157892",
-        "197015606336332",
-    ),
-    (
-        22,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day22.txt.age")),
-        "17262627539",
-        "1986",
-    ),
-    (
-        23,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day23.txt.age")),
-        "1149",
-        "as,co,do,kh,km,mc,np,nt,un,uq,wc,wz,yo",
-    ),
-    (
-        24,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day24.txt.age")),
-        "61495910098126",
-        "css,cwt,gdd,jmv,pqt,z05,z09,z37",
-    ),
-    (
-        25,
-        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day25.txt.age")),
-        "3146",
-        "",
-    ),
-];
+/// The unencrypted manifest from before the answers were encrypted, if there is one instead of
+/// the encrypted manifest at `path`.
+fn unencrypted_answers(path: &Path) -> Option<PathBuf> {
+    let unencrypted = path.with_extension("");
+    (is_encrypted(path) && !path.exists() && unencrypted.exists()).then_some(unencrypted)
+}
+
+/// Loads the answers manifest, decrypting it with the key of the inputs if it is encrypted.
+fn load_answers(args: &Args) -> Result<Answers> {
+    let path = answers_path(args);
+    if let Some(unencrypted) = unencrypted_answers(&path) {
+        println!(
+            "Warning: the answers in {} are not encrypted and easy to reverse, add or update \
+             an input with add-input to move them to {}",
+            unencrypted.display(),
+            path.display()
+        );
+        return Answers::load(&unencrypted);
+    }
+    if !is_encrypted(&path) {
+        return Answers::load(&path);
+    }
+    let key = inputs::key_from_args(&args.inputs, args.identity.as_deref())?;
+    Answers::load_encrypted(&path, &key)
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    match &args.command {
+        Some(Command::BuildPlugins {
+            out_dir,
+            work_dir,
+            participants,
+            wasm,
+        }) => {
+            for (name, outcome) in builder::build_all(participants, work_dir, out_dir, *wasm)? {
+                if let BuildOutcome::Failed(diagnostics) = outcome {
+                    println!("Build of {name} failed:\n{diagnostics}");
+                }
+            }
+            return Ok(());
+        }
+        Some(Command::AddInput {
+            day,
+            name,
+            input,
+            part1,
+            part2,
+        }) => {
+            return add_input(&args, *day, name.clone(), input.as_deref(), part1, part2);
+        }
+        Some(Command::RunOnce { user, day }) => {
+            let (participants, _) = load_participants(args.plugin_dir.as_deref())?;
+            let participant = participants
+                .iter()
+                .find(|p| &p.name == user)
+                .ok_or_else(|| eyre!("unknown participant {user}"))?;
+            return cold::run(participant, *day);
+        }
+        Some(Command::Fuzz { .. } | Command::Differential { .. } | Command::Verify { .. })
+        | None => {}
+    }
+
+    let config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    let criterion = config.criterion.or(args.criterion.clone()).settings()?;
+    let mut timeouts = config.timeouts;
+    for timeout in &args.timeouts {
+        timeout.apply(&mut timeouts);
+    }
+    // only checks that the counters are available, every measurement opens its own
+    let counters = args.counters
+        && match Counters::open() {
+            Ok(_) => true,
+            Err(e) => {
+                println!(
+                    "Warning: hardware counters are not available ({e}), continuing without them"
+                );
+                false
+            }
+        };
+    let wasm = match (&args.plugin_dir, args.wasm_fuel) {
+        (Some(plugin_dir), true) => Some(wasm::Runtime::load(plugin_dir, args.wasm_fuel_limit)?),
+        _ => None,
+    };
+    let options = BenchOptions {
+        criterion_dir: args.criterion_dir.clone(),
+        timeouts,
+        criterion,
+        bench_inputs: args.bench_inputs,
+        shard: args.shard,
+        cold_runs: args.cold_runs,
+        memoization_ratio: args.memoization_ratio,
+        strict: args.strict,
+        counters,
+        wasm,
+        determinism_runs: args.determinism_runs,
+        determinism_processes: args.determinism_processes,
+        robustness: args.robustness,
+        plugin_dir: args.plugin_dir.clone(),
+    };
+    if let Some(Command::Fuzz {
+        user,
+        day,
+        iterations,
+        seed,
+        time_limit,
+        corpus_dir,
+    }) = &args.command
+    {
+        if !(*time_limit > 0.0 && time_limit.is_finite()) {
+            return Err(eyre!("the time limit has to be positive"));
+        }
+        let mut answers = load_answers(&args)?;
+        let inputs = inputs::from_args(&args.inputs, args.plaintext, args.identity.as_deref())?;
+        let (mut participants, _) = load_participants(args.plugin_dir.as_deref())?;
+        if let Some(user) = user {
+            participants.retain(|p| &p.name == user);
+            if participants.is_empty() {
+                return Err(eyre!("unknown participant {user}"));
+            }
+        }
+        if let Some(day) = day {
+            answers.inputs.retain(|a| a.day == *day);
+        }
+        let fuzz_options = fuzz::FuzzOptions {
+            iterations: *iterations,
+            seed: seed.unwrap_or_else(rand::random),
+            time_limit: Duration::from_secs_f64(*time_limit),
+            corpus_dir: corpus_dir.clone(),
+        };
+        return fuzz::fuzz(
+            &participants,
+            inputs.as_ref(),
+            &answers,
+            &options,
+            &fuzz_options,
+        );
+    }
+    if let Some(Command::Verify { user, day }) = &args.command {
+        let mut answers = load_answers(&args)?;
+        let inputs = inputs::from_args(&args.inputs, args.plaintext, args.identity.as_deref())?;
+        let (mut participants, mut failed) = load_participants(args.plugin_dir.as_deref())?;
+        if let Some(user) = user {
+            participants.retain(|p| &p.name == user);
+            failed.retain(|name| name == user);
+            if participants.is_empty() && failed.is_empty() {
+                return Err(eyre!("unknown participant {user}"));
+            }
+        }
+        if let Some(day) = day {
+            answers.inputs.retain(|a| a.day == *day);
+        }
+        return verify::verify(
+            &participants,
+            &failed,
+            inputs.as_ref(),
+            &answers.inputs,
+            &options,
+        );
+    }
+    if let Some(Command::Differential {
+        dir,
+        generator,
+        count,
+        seed,
+        day,
+    }) = &args.command
+    {
+        let source = match (dir, generator) {
+            (Some(dir), _) => differential::Source::Dir(dir.clone()),
+            (None, Some(program)) => differential::Source::Generator {
+                program: program.clone(),
+                count: *count,
+                seed: seed.unwrap_or_else(rand::random),
+            },
+            (None, None) => unreachable!("clap requires --dir or --generator"),
+        };
+        let days: Vec<u8> = match day {
+            Some(day) if !(1..=25).contains(day) => {
+                return Err(eyre!("day must be between 1 and 25"))
+            }
+            Some(day) => vec![*day],
+            None => (1..=25).collect(),
+        };
+        let (participants, _) = load_participants(args.plugin_dir.as_deref())?;
+        return differential::run(&participants, &source, &days, &options);
+    }
+    let answers = load_answers(&args)?;
+    let isolation = isolation::setup(args.pin_cpu, args.high_priority)?;
+    // the plugins replace the built-in participants of the same name
+    let mut revisions: BTreeMap<String, String> = static_revisions()
+        .into_iter()
+        .map(|(name, rev)| (name.to_string(), rev.to_string()))
+        .collect();
+    if let Some(plugin_dir) = &args.plugin_dir {
+        revisions.extend(builder::revisions(plugin_dir)?);
+    }
+    let seed = args.seed.unwrap_or_else(rand::random);
+    if args.order != Order::Sequential {
+        println!("Measuring in {} order with seed {seed}", args.order);
+    }
+    let fingerprint = fingerprint::collect(revisions);
+    let mut results = ResultsWriter::create(&args.results)?;
+    results.write(&Event::Run(RunInfo {
+        timeouts: options.timeouts.clone(),
+        cold_runs: options.cold_runs,
+        strict: options.strict,
+        isolation,
+        fingerprint: fingerprint.clone(),
+        shard: options.shard,
+        order: args.order,
+        seed: (args.order != Order::Sequential).then_some(seed),
+    }))?;
+    let cache = match &args.cache {
+        Some(dir) => Some(Cache::new(dir, &fingerprint, &options)?),
+        None => None,
+    };
+    let inputs = inputs::from_args(&args.inputs, args.plaintext, args.identity.as_deref())?;
+    let held_out = match &args.held_out {
+        Some(dir) => Some(HeldOut::load(
+            dir,
+            args.held_out_identity.as_deref(),
+            &answers,
+        )?),
+        None => None,
+    };
+    let (participants, failed) = load_participants(args.plugin_dir.as_deref())?;
+    for name in failed {
+        println!("{name}-build: build failed");
+        results.write(&Event::BuildFailed { user: name })?;
+    }
+    let cases = test_cases(inputs.as_ref(), &answers, held_out.as_ref());
+    let days: Vec<u8> = cases.keys().copied().collect();
+    let schedule = schedule::arrange(&participants, &days, options.shard, args.order, seed);
+    let mut calibration_ns =
+        calibration::run(CalibrationPoint::Start, args.calibration_runs, &mut results)?;
+    for (i, &(participant, day)) in schedule.iter().enumerate() {
+        if i == schedule.len() / 2 {
+            calibration_ns = calibration::run(
+                CalibrationPoint::Middle,
+                args.calibration_runs,
+                &mut results,
+            )?;
+        }
+        bench_day(
+            participant,
+            &cases[&day],
+            &options,
+            cache.as_ref(),
+            calibration_ns,
+            &mut results,
+        )?;
+    }
+    calibration::run(CalibrationPoint::End, args.calibration_runs, &mut results)?;
+    // a shard only uses the entries of its own participants and days
+    if let (Some(cache), None) = (&cache, options.shard) {
+        cache.prune()?;
+    }
+    Criterion::default().final_summary();
+    Ok(())
+}
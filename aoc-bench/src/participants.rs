@@ -0,0 +1,188 @@
+//! The participant registry in `participants.toml`.
+//!
+//! This module is shared between the build script, which generates the statically linked
+//! participants, and the `build-plugins` subcommand, which builds every participant as a
+//! separate plugin. It only depends on `serde` and `toml` for that reason.
+
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct ParticipantsFile {
+    #[serde(default)]
+    participant: Vec<Participant>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Participant {
+    pub name: String,
+    #[serde(rename = "crate", default = "default_crate")]
+    pub crate_name: String,
+    #[serde(default = "default_solutions")]
+    pub solutions: String,
+    git: Option<String>,
+    rev: Option<String>,
+    branch: Option<String>,
+    tag: Option<String>,
+    path: Option<PathBuf>,
+}
+
+fn default_crate() -> String {
+    "meta".to_string()
+}
+
+fn default_solutions() -> String {
+    "AoC2024".to_string()
+}
+
+/// Reads and validates `participants.toml`.
+pub fn load(path: &Path) -> Result<Vec<Participant>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+    let file: ParticipantsFile =
+        toml::from_str(&content).map_err(|e| format!("cannot parse {}: {e}", path.display()))?;
+
+    let mut keys = BTreeSet::new();
+    for p in &file.participant {
+        // benchmark ids are of the form "username-dayXX-phase" and get split on '-'
+        if p.name.is_empty() || p.name.contains(char::is_whitespace) || p.name.contains('-') {
+            return Err(format!(
+                "participant name {:?} must be non-empty and contain neither whitespace nor '-'",
+                p.name
+            ));
+        }
+        if !keys.insert(p.dependency_key()) {
+            return Err(format!("participant {} is listed twice", p.name));
+        }
+        let refs = [&p.rev, &p.branch, &p.tag]
+            .iter()
+            .filter(|r| r.is_some())
+            .count();
+        match (&p.git, &p.path) {
+            (Some(_), None) if refs == 0 => {
+                return Err(format!(
+                    "participant {}: git sources need one of `rev`, `branch` or `tag`",
+                    p.name
+                ))
+            }
+            (Some(_), None) if refs > 1 => {
+                return Err(format!(
+                    "participant {}: only one of `rev`, `branch` or `tag` may be set",
+                    p.name
+                ))
+            }
+            (None, Some(_)) if refs > 0 => {
+                return Err(format!(
+                    "participant {}: `rev`, `branch` and `tag` only apply to git sources",
+                    p.name
+                ))
+            }
+            (Some(_), None) | (None, Some(_)) => {}
+            _ => {
+                return Err(format!(
+                    "participant {}: exactly one of `git` or `path` must be set",
+                    p.name
+                ))
+            }
+        }
+    }
+    Ok(file.participant)
+}
+
+impl Participant {
+    /// the key of the dependency in `aoc-bench/Cargo.toml`, e.g. `dkales-aoc`
+    pub fn dependency_key(&self) -> String {
+        let sanitized: String = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        format!("{sanitized}-aoc")
+    }
+
+    /// The git revision the solutions crate is locked to in `lockfile`, the contents of a
+    /// `Cargo.lock`. `None` for local paths.
+    pub fn locked_revision(&self, lockfile: &str) -> Option<String> {
+        let git = self.git.as_deref()?.trim_end_matches(".git");
+        let lockfile: toml::Value = toml::from_str(lockfile).ok()?;
+        lockfile
+            .get("package")?
+            .as_array()?
+            .iter()
+            .find_map(|package| {
+                if package.get("name")?.as_str()? != self.crate_name {
+                    return None;
+                }
+                // e.g. git+https://github.com/dkales/adventofcode2024?branch=main#021e3da5...
+                let source = package.get("source")?.as_str()?.strip_prefix("git+")?;
+                let (url, rev) = source.split_once('#')?;
+                let url = url.split_once('?').map_or(url, |(url, _)| url);
+                (url.trim_end_matches(".git") == git).then(|| rev.to_string())
+            })
+    }
+
+    /// The dependency on the solutions crate as a line of a `Cargo.toml`.
+    /// Local paths in `participants.toml` are relative to `root`, the directory containing it,
+    /// and are rewritten relative to `manifest_dir`, the directory of the generated manifest.
+    pub fn dependency_line(
+        &self,
+        key: &str,
+        root: &Path,
+        manifest_dir: &Path,
+        extra: &str,
+    ) -> String {
+        let quote = |s: &str| toml::Value::String(s.to_string()).to_string();
+        let mut fields = vec![format!("package = {}", quote(&self.crate_name))];
+        if let Some(git) = &self.git {
+            fields.push(format!("git = {}", quote(git)));
+            for (k, v) in [
+                ("rev", &self.rev),
+                ("branch", &self.branch),
+                ("tag", &self.tag),
+            ] {
+                if let Some(v) = v {
+                    fields.push(format!("{k} = {}", quote(v)));
+                }
+            }
+        }
+        if let Some(path) = &self.path {
+            let path = if path.is_absolute() {
+                path.clone()
+            } else {
+                relative_to(&root.join(path), manifest_dir)
+            };
+            fields.push(format!("path = {}", quote(&path.to_string_lossy())));
+        }
+        if !extra.is_empty() {
+            fields.push(extra.to_string());
+        }
+        format!("{key} = {{ {} }}", fields.join(", "))
+    }
+}
+
+/// Expresses `path` relative to `base`, both have to be absolute or both relative to the
+/// same directory.
+fn relative_to(path: &Path, base: &Path) -> PathBuf {
+    let path: Vec<_> = path.components().collect();
+    let base: Vec<_> = base.components().collect();
+    let common = path.iter().zip(&base).take_while(|(a, b)| a == b).count();
+    let mut rel = PathBuf::new();
+    for _ in common..base.len() {
+        rel.push("..");
+    }
+    for c in &path[common..] {
+        rel.push(c);
+    }
+    rel
+}
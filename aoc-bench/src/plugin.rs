@@ -0,0 +1,172 @@
+use std::{
+    ffi::c_void,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use aoc_traits::ffi::{self, FfiBuffer, FfiDay, FfiStatus};
+use color_eyre::eyre::{eyre, Context, Result};
+use libloading::Library;
+
+use crate::solver::{Day, DaySolver, Participant};
+
+/// A day of a solutions plugin, i.e., a `cdylib` using `aoc_traits::export_solutions!`.
+#[derive(Clone)]
+pub struct PluginDay {
+    // keeps the library loaded as long as any of its days are in use
+    _library: Arc<Library>,
+    day: FfiDay,
+    free_buffer: unsafe extern "C" fn(FfiBuffer),
+}
+
+/// The parsed input of a plugin day, owned by the plugin.
+pub struct PluginParsed<'a> {
+    handle: *mut c_void,
+    drop_parsed: unsafe extern "C" fn(*mut c_void, *mut FfiBuffer) -> FfiStatus,
+    free_buffer: unsafe extern "C" fn(FfiBuffer),
+    _input: PhantomData<&'a str>,
+}
+
+impl Drop for PluginParsed<'_> {
+    fn drop(&mut self) {
+        let mut out = FfiBuffer::empty();
+        // SAFETY: the handle was created by the parse function of the same day
+        let status = unsafe { (self.drop_parsed)(self.handle, &mut out) };
+        if status == FfiStatus::Panic {
+            let message = take(self.free_buffer, out);
+            // panicking again while the harness unwinds would abort it
+            if !std::thread::panicking() {
+                panic!("{message}");
+            }
+        }
+    }
+}
+
+/// Copies a buffer filled by the plugin and gives it back to the plugin.
+fn take(free_buffer: unsafe extern "C" fn(FfiBuffer), out: FfiBuffer) -> String {
+    // SAFETY: the buffer was filled in by the plugin
+    let s = unsafe { out.as_str() }.to_string();
+    unsafe { free_buffer(out) };
+    s
+}
+
+impl PluginDay {
+    /// Takes ownership of a buffer filled by the plugin, re-raising panics of the plugin
+    /// in the harness so they are handled like panics of native solutions.
+    fn finish(&self, status: FfiStatus, out: FfiBuffer) -> String {
+        let s = take(self.free_buffer, out);
+        match status {
+            FfiStatus::Ok => s,
+            FfiStatus::Panic => panic!("{s}"),
+        }
+    }
+
+    /// Re-raises a panic of a call that only fills in the buffer when it panics.
+    fn check(&self, status: FfiStatus, out: FfiBuffer) {
+        if status == FfiStatus::Panic {
+            self.finish(status, out);
+        }
+    }
+}
+
+impl DaySolver for PluginDay {
+    type Parsed<'a> = PluginParsed<'a>;
+    type Part1 = String;
+    type Part2 = String;
+
+    fn is_implemented(&self) -> bool {
+        self.day.implemented
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Self::Parsed<'a> {
+        let mut handle = std::ptr::null_mut();
+        let mut out = FfiBuffer::empty();
+        // SAFETY: the returned handle borrows from input, which outlives PluginParsed<'a>
+        let status =
+            unsafe { (self.day.parse)(input.as_ptr(), input.len(), &mut handle, &mut out) };
+        self.check(status, out);
+        PluginParsed {
+            handle,
+            drop_parsed: self.day.drop_parsed,
+            free_buffer: self.free_buffer,
+            _input: PhantomData,
+        }
+    }
+
+    fn part1(&self, parsed: &Self::Parsed<'_>) -> Self::Part1 {
+        let mut out = FfiBuffer::empty();
+        let status = unsafe { (self.day.part1)(parsed.handle, &mut out) };
+        self.finish(status, out)
+    }
+
+    fn part2(&self, parsed: &Self::Parsed<'_>) -> Self::Part2 {
+        let mut out = FfiBuffer::empty();
+        let status = unsafe { (self.day.part2)(parsed.handle, &mut out) };
+        self.finish(status, out)
+    }
+
+    fn bench_part1(&self, parsed: &Self::Parsed<'_>) {
+        let mut out = FfiBuffer::empty();
+        let status = unsafe { (self.day.bench_part1)(parsed.handle, &mut out) };
+        self.check(status, out);
+    }
+
+    fn bench_part2(&self, parsed: &Self::Parsed<'_>) {
+        let mut out = FfiBuffer::empty();
+        let status = unsafe { (self.day.bench_part2)(parsed.handle, &mut out) };
+        self.check(status, out);
+    }
+}
+
+/// Loads a single solutions plugin. The participant is named after the file,
+/// without the `lib` prefix and the extension.
+pub fn load(path: &Path) -> Result<Participant> {
+    let name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .map(|s| s.strip_prefix("lib").unwrap_or(s).to_string())
+        .ok_or_else(|| eyre!("invalid plugin file name {}", path.display()))?;
+    // SAFETY: loading a library runs its initializers, we trust the plugins we benchmark
+    let library = unsafe { Library::new(path) }
+        .with_context(|| format!("cannot load plugin {}", path.display()))?;
+    let solutions = unsafe {
+        let entry_point = library
+            .get::<ffi::EntryPoint>(ffi::ENTRY_POINT)
+            .with_context(|| format!("{} has no solutions entry point", path.display()))?;
+        &*entry_point()
+    };
+    if solutions.abi_version != ffi::ABI_VERSION {
+        return Err(eyre!(
+            "{} was built for plugin ABI v{}, but the harness uses v{}",
+            path.display(),
+            solutions.abi_version,
+            ffi::ABI_VERSION
+        ));
+    }
+    let days = solutions.days;
+    let free_buffer = solutions.free_buffer;
+    let library = Arc::new(library);
+    let days = days
+        .into_iter()
+        .map(|day| {
+            Box::new(PluginDay {
+                _library: library.clone(),
+                day,
+                free_buffer,
+            }) as Box<dyn Day>
+        })
+        .collect();
+    Ok(Participant::new(name, days))
+}
+
+/// Loads all plugins in `dir`, sorted by file name.
+pub fn load_dir(dir: &Path) -> Result<Vec<Participant>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("cannot read plugin dir {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some(std::env::consts::DLL_EXTENSION))
+        .collect();
+    paths.sort();
+    paths.iter().map(|p| load(p)).collect()
+}
@@ -0,0 +1,61 @@
+//! Writes the results file read by aoc-bench-parser, see [`aoc_bench_results`].
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use aoc_bench_results::{Event, Timing};
+use color_eyre::eyre::{eyre, Context, Result};
+
+pub struct ResultsWriter {
+    file: BufWriter<File>,
+}
+
+impl ResultsWriter {
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("cannot create results file {}", path.display()))?;
+        Ok(ResultsWriter {
+            file: BufWriter::new(file),
+        })
+    }
+
+    /// Appends an event, flushing it right away so a crash doesn't lose the earlier results.
+    pub fn write(&mut self, event: &Event) -> Result<()> {
+        serde_json::to_writer(&mut self.file, event)?;
+        self.file.write_all(b"\n")?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+/// Reads the timings of the benchmark `id` from the criterion output in `criterion_dir`.
+pub fn read_timing(criterion_dir: &Path, id: &str) -> Result<Timing> {
+    let dir = criterion_dir.join(id).join("new");
+    let read = |file: &str| -> Result<serde_json::Value> {
+        let path = dir.join(file);
+        let file =
+            File::open(&path).with_context(|| format!("trying to open {}", path.display()))?;
+        Ok(serde_json::from_reader(file)?)
+    };
+    let estimates = read("estimates.json")?;
+    let sample = read("sample.json")?;
+    let estimate = |value: &serde_json::Value| {
+        value
+            .as_f64()
+            .ok_or_else(|| eyre!("invalid estimates of benchmark {id}"))
+    };
+    let iters = sample["iters"]
+        .as_array()
+        .ok_or_else(|| eyre!("invalid samples of benchmark {id}"))?;
+    Ok(Timing {
+        median_ns: estimate(&estimates["median"]["point_estimate"])?,
+        median_lower_ns: estimate(&estimates["median"]["confidence_interval"]["lower_bound"])?,
+        median_upper_ns: estimate(&estimates["median"]["confidence_interval"]["upper_bound"])?,
+        mean_ns: estimate(&estimates["mean"]["point_estimate"])?,
+        samples: iters.len(),
+        iterations: iters.iter().filter_map(|i| i.as_f64()).sum::<f64>() as u64,
+    })
+}
@@ -0,0 +1,52 @@
+//! Checks that solutions don't depend on the harness trimming the input, by feeding them the
+//! [`Variant`]s of the inputs they passed.
+
+use aoc_bench_results::{RobustnessResult, Variant};
+
+use crate::{solver::Day, BenchOptions, TestCase};
+
+/// Turns an input into a variant.
+fn variant_input(variant: Variant, input: &str) -> String {
+    let mut untrimmed = input.to_string();
+    if !untrimmed.ends_with('\n') {
+        untrimmed.push('\n');
+    }
+    match variant {
+        Variant::Untrimmed => untrimmed,
+        Variant::Crlf => untrimmed.replace("\r\n", "\n").replace('\n', "\r\n"),
+        Variant::TrailingBlankLine => untrimmed + "\n",
+    }
+}
+
+/// Runs the day on every variant of the input and checks the answers.
+pub fn check(
+    day: &dyn Day,
+    username: &str,
+    case: &TestCase,
+    options: &BenchOptions,
+) -> Vec<RobustnessResult> {
+    Variant::ALL
+        .into_iter()
+        .map(|variant| {
+            let input = variant_input(variant, case.input);
+            let error = match day.answers(&input, options.run_timeout(case.day)) {
+                Ok((part1, _)) if !case.answers.check_part1(&part1) => {
+                    Some(format!("part1 answered {part1:?}"))
+                }
+                Ok((_, part2)) if !case.answers.check_part2(&part2) => {
+                    Some(format!("part2 answered {part2:?}"))
+                }
+                Ok(_) => None,
+                Err(e) => Some(e.details().unwrap_or_else(|| e.status().to_string())),
+            };
+            RobustnessResult {
+                user: username.to_string(),
+                day: case.day,
+                input: case.name.to_string(),
+                variant,
+                passed: error.is_none(),
+                error,
+            }
+        })
+        .collect()
+}
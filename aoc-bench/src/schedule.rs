@@ -0,0 +1,132 @@
+//! The order in which the days of the participants are measured. Runners heat up and drift over
+//! a run, so with a fixed order the same participants always get the worst conditions. Shuffling
+//! spreads that out, and interleaving measures everybody's solution of a day within a short
+//! time, so they are compared under the same conditions.
+
+use aoc_bench_results::{Order, Shard};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::solver::Participant;
+
+/// The (participant, day) pairs of `shard` in the order they are measured. The same seed
+/// always gives the same order.
+pub fn arrange<'a>(
+    participants: &'a [Participant],
+    days: &[u8],
+    shard: Option<Shard>,
+    order: Order,
+    seed: u64,
+) -> Vec<(&'a Participant, u8)> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let included = |p: &Participant, day: u8| shard.is_none_or(|s| s.includes(&p.name, day));
+    match order {
+        Order::Sequential => participants
+            .iter()
+            .flat_map(|p| days.iter().map(move |&day| (p, day)))
+            .filter(|&(p, day)| included(p, day))
+            .collect(),
+        Order::Shuffled => {
+            let mut pairs: Vec<_> = participants
+                .iter()
+                .flat_map(|p| days.iter().map(move |&day| (p, day)))
+                .filter(|&(p, day)| included(p, day))
+                .collect();
+            pairs.shuffle(&mut rng);
+            pairs
+        }
+        Order::Interleaved => {
+            let mut pairs = Vec::new();
+            for &day in days {
+                let mut day_pairs: Vec<_> = participants
+                    .iter()
+                    .filter(|p| included(p, day))
+                    .map(|p| (p, day))
+                    .collect();
+                // a new order every day, so nobody always goes first
+                day_pairs.shuffle(&mut rng);
+                pairs.extend(day_pairs);
+            }
+            pairs
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{Day, Native};
+
+    fn participants(names: &[&str]) -> Vec<Participant> {
+        names
+            .iter()
+            .map(|name| {
+                let days = (0..25)
+                    .map(|_| Box::new(Native::<()>::default()) as Box<dyn Day>)
+                    .collect();
+                Participant::new(*name, days)
+            })
+            .collect()
+    }
+
+    fn pairs(schedule: &[(&Participant, u8)]) -> Vec<(String, u8)> {
+        schedule
+            .iter()
+            .map(|(p, day)| (p.name.clone(), *day))
+            .collect()
+    }
+
+    #[test]
+    fn sequential_measures_each_participant_in_turn() {
+        let participants = participants(&["a", "b"]);
+        let schedule = arrange(&participants, &[1, 2], None, Order::Sequential, 0);
+        let expected = [("a", 1), ("a", 2), ("b", 1), ("b", 2)].map(|(p, d)| (p.to_string(), d));
+        assert_eq!(pairs(&schedule), expected);
+    }
+
+    #[test]
+    fn shuffled_is_a_permutation_repeated_by_the_seed() {
+        let participants = participants(&["a", "b", "c", "d"]);
+        let days: Vec<u8> = (1..=25).collect();
+        let shuffled = pairs(&arrange(&participants, &days, None, Order::Shuffled, 7));
+        assert_eq!(
+            shuffled,
+            pairs(&arrange(&participants, &days, None, Order::Shuffled, 7))
+        );
+        let mut sorted = shuffled.clone();
+        sorted.sort();
+        let sequential = pairs(&arrange(&participants, &days, None, Order::Sequential, 7));
+        assert_eq!(sorted, sequential);
+        assert_ne!(shuffled, sequential);
+    }
+
+    #[test]
+    fn interleaved_measures_all_participants_of_a_day_together() {
+        let participants = participants(&["a", "b", "c"]);
+        let schedule = pairs(&arrange(
+            &participants,
+            &[3, 1, 2],
+            None,
+            Order::Interleaved,
+            1,
+        ));
+        let days: Vec<u8> = schedule.iter().map(|&(_, day)| day).collect();
+        assert_eq!(days, [3, 3, 3, 1, 1, 1, 2, 2, 2]);
+    }
+
+    #[test]
+    fn the_shards_split_the_pairs_between_them() {
+        let participants = participants(&["a", "b", "c"]);
+        let days: Vec<u8> = (1..=25).collect();
+        for order in [Order::Sequential, Order::Shuffled, Order::Interleaved] {
+            let mut all: Vec<(String, u8)> = (1..=3)
+                .flat_map(|index| {
+                    let shard = Shard { index, count: 3 };
+                    pairs(&arrange(&participants, &days, Some(shard), order, 5))
+                })
+                .collect();
+            all.sort();
+            let sequential = pairs(&arrange(&participants, &days, None, Order::Sequential, 5));
+            assert_eq!(all, sequential, "{order}");
+        }
+    }
+}
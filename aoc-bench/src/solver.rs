@@ -0,0 +1,201 @@
+use std::{
+    any::TypeId,
+    collections::BTreeMap,
+    fmt::Display,
+    marker::PhantomData,
+    panic::{RefUnwindSafe, UnwindSafe},
+    time::{Duration, Instant},
+};
+
+use aoc_bench_results::{HardwareCounters, Phase};
+use aoc_traits::{AdventOfCodeDay, AdventOfCodeSolutions};
+use criterion::black_box;
+
+use crate::{bench_aoc_day, counters::Counters, solve, BenchOptions, ExecutionError, TestCase};
+
+/// The solution of a single day as seen by the harness.
+///
+/// This abstracts over solutions linked into the harness ([`Native`]) and solutions loaded
+/// from a plugin at runtime ([`crate::plugin::PluginDay`]), so that both are benchmarked
+/// by the same monomorphized code.
+pub trait DaySolver: Clone + Send + UnwindSafe + RefUnwindSafe + 'static {
+    type Parsed<'a>;
+    type Part1: Display;
+    type Part2: Display;
+
+    fn is_implemented(&self) -> bool;
+    fn parse<'a>(&self, input: &'a str) -> Self::Parsed<'a>;
+    fn part1(&self, parsed: &Self::Parsed<'_>) -> Self::Part1;
+    fn part2(&self, parsed: &Self::Parsed<'_>) -> Self::Part2;
+
+    /// Solves part 1 without formatting the answer, which is what the benchmarks measure.
+    fn bench_part1(&self, parsed: &Self::Parsed<'_>) {
+        black_box(self.part1(parsed));
+    }
+
+    /// Solves part 2 without formatting the answer, which is what the benchmarks measure.
+    fn bench_part2(&self, parsed: &Self::Parsed<'_>) {
+        black_box(self.part2(parsed));
+    }
+}
+
+/// A day of solutions linked into the harness at compile time.
+#[cfg_attr(not(feature = "static-participants"), allow(dead_code))]
+pub struct Native<D>(PhantomData<fn() -> D>);
+
+impl<D> Default for Native<D> {
+    fn default() -> Self {
+        Native(PhantomData)
+    }
+}
+
+impl<D> Clone for Native<D> {
+    fn clone(&self) -> Self {
+        Native(PhantomData)
+    }
+}
+
+impl<D: AdventOfCodeDay + 'static> DaySolver for Native<D> {
+    type Parsed<'a> = D::ParsedInput<'a>;
+    type Part1 = D::Part1Output;
+    type Part2 = D::Part2Output;
+
+    fn is_implemented(&self) -> bool {
+        TypeId::of::<D>() != TypeId::of::<()>()
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Self::Parsed<'a> {
+        D::parse_input(input)
+    }
+
+    fn part1(&self, parsed: &Self::Parsed<'_>) -> Self::Part1 {
+        D::solve_part1(parsed)
+    }
+
+    fn part2(&self, parsed: &Self::Parsed<'_>) -> Self::Part2 {
+        D::solve_part2(parsed)
+    }
+}
+
+/// A [`DaySolver`] with the concrete types erased, so days can be stored in a list.
+pub trait Day: Send + Sync {
+    fn bench(
+        &self,
+        username: &str,
+        case: &TestCase,
+        options: &BenchOptions,
+        benchmark: bool,
+    ) -> (
+        Result<(), ExecutionError>,
+        Result<(), ExecutionError>,
+        Result<(), ExecutionError>,
+    );
+
+    /// Runs parse, part1 and part2 once without any checks and returns how long each of them
+    /// took, together with the answers.
+    fn run_once(&self, input: &str) -> ([Duration; 3], String, String);
+
+    /// Parses the untrimmed input and solves both parts in another thread, returning the
+    /// answers.
+    fn answers(&self, input: &str, timeout: Duration) -> Result<(String, String), ExecutionError>;
+
+    /// Counts the hardware events of every phase on the trimmed input, see
+    /// [`crate::counters::count_phases`].
+    fn count(&self, input: &str, counters: &Counters) -> BTreeMap<Phase, HardwareCounters>;
+}
+
+impl<S: DaySolver + Sync> Day for S {
+    fn bench(
+        &self,
+        username: &str,
+        case: &TestCase,
+        options: &BenchOptions,
+        benchmark: bool,
+    ) -> (
+        Result<(), ExecutionError>,
+        Result<(), ExecutionError>,
+        Result<(), ExecutionError>,
+    ) {
+        bench_aoc_day(self.clone(), username, case, options, benchmark)
+    }
+
+    fn run_once(&self, input: &str) -> ([Duration; 3], String, String) {
+        let start = Instant::now();
+        let parsed = self.parse(black_box(input.trim()));
+        let parse = start.elapsed();
+        let start = Instant::now();
+        let part1 = black_box(self.part1(&parsed));
+        let dur_part1 = start.elapsed();
+        let start = Instant::now();
+        let part2 = black_box(self.part2(&parsed));
+        let dur_part2 = start.elapsed();
+        (
+            [parse, dur_part1, dur_part2],
+            part1.to_string(),
+            part2.to_string(),
+        )
+    }
+
+    fn answers(&self, input: &str, timeout: Duration) -> Result<(String, String), ExecutionError> {
+        solve(self.clone(), input, timeout)
+    }
+
+    fn count(&self, input: &str, counters: &Counters) -> BTreeMap<Phase, HardwareCounters> {
+        crate::counters::count_phases(self, input, counters)
+    }
+}
+
+/// All 25 days of a single participant.
+pub struct Participant {
+    pub name: String,
+    days: Vec<Box<dyn Day>>,
+}
+
+impl Participant {
+    pub fn new(name: impl Into<String>, days: Vec<Box<dyn Day>>) -> Self {
+        assert_eq!(days.len(), 25, "a participant has exactly 25 days");
+        Participant {
+            name: name.into(),
+            days,
+        }
+    }
+
+    #[cfg_attr(not(feature = "static-participants"), allow(dead_code))]
+    pub fn from_solutions<S: AdventOfCodeSolutions + 'static>(name: impl Into<String>) -> Self {
+        Self::new(
+            name,
+            vec![
+                Box::new(Native::<S::Day01>::default()),
+                Box::new(Native::<S::Day02>::default()),
+                Box::new(Native::<S::Day03>::default()),
+                Box::new(Native::<S::Day04>::default()),
+                Box::new(Native::<S::Day05>::default()),
+                Box::new(Native::<S::Day06>::default()),
+                Box::new(Native::<S::Day07>::default()),
+                Box::new(Native::<S::Day08>::default()),
+                Box::new(Native::<S::Day09>::default()),
+                Box::new(Native::<S::Day10>::default()),
+                Box::new(Native::<S::Day11>::default()),
+                Box::new(Native::<S::Day12>::default()),
+                Box::new(Native::<S::Day13>::default()),
+                Box::new(Native::<S::Day14>::default()),
+                Box::new(Native::<S::Day15>::default()),
+                Box::new(Native::<S::Day16>::default()),
+                Box::new(Native::<S::Day17>::default()),
+                Box::new(Native::<S::Day18>::default()),
+                Box::new(Native::<S::Day19>::default()),
+                Box::new(Native::<S::Day20>::default()),
+                Box::new(Native::<S::Day21>::default()),
+                Box::new(Native::<S::Day22>::default()),
+                Box::new(Native::<S::Day23>::default()),
+                Box::new(Native::<S::Day24>::default()),
+                Box::new(Native::<S::Day25>::default()),
+            ],
+        )
+    }
+
+    /// The solution for `day`, 1-based.
+    pub fn day(&self, day: u8) -> &dyn Day {
+        self.days[usize::from(day) - 1].as_ref()
+    }
+}
@@ -0,0 +1,207 @@
+//! A quick check for participants before they push: runs the answer and timeout checks of every
+//! day without benchmarking, on all cores, and prints a grid of the outcomes.
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use aoc_bench_results::{Phase, Status};
+use color_eyre::eyre::{eyre, Result};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    answers::ExpectedAnswers, inputs::InputProvider, solver::Participant, BenchOptions,
+    ExecutionError, TestCase,
+};
+
+/// The outcome of a day of a participant over all of its inputs.
+#[derive(Default)]
+struct DayOutcome {
+    inputs: usize,
+    /// the status of the first failed phase of every input that failed
+    failures: Vec<Status>,
+    not_implemented: bool,
+    /// e.g. `alice day01-franco part1: wrong answer "123"`
+    details: Vec<String>,
+}
+
+impl DayOutcome {
+    /// e.g. `✅`, `-` or `🐌 1/2`
+    fn cell(&self) -> String {
+        if self.not_implemented {
+            return Status::NotImplemented.symbol().to_string();
+        }
+        match self.failures.first() {
+            None => Status::Ok.symbol().to_string(),
+            Some(status) if self.failures.len() == self.inputs => status.symbol().to_string(),
+            Some(status) => format!(
+                "{} {}/{}",
+                status.symbol(),
+                self.failures.len(),
+                self.inputs
+            ),
+        }
+    }
+}
+
+/// Checks the participants on every input in `answers` and prints the grid.
+/// Participants in `build_failed` are shown as such. Fails if any check failed, but not for days
+/// that are not implemented.
+pub fn verify(
+    participants: &[Participant],
+    build_failed: &[String],
+    inputs: &dyn InputProvider,
+    answers: &[ExpectedAnswers],
+    options: &BenchOptions,
+) -> Result<()> {
+    // the input providers aren't shared between threads, so all inputs are read upfront
+    let mut cases: BTreeMap<u8, Vec<(String, &ExpectedAnswers)>> = BTreeMap::new();
+    for answers in answers {
+        let input = inputs.input(&answers.name)?;
+        cases.entry(answers.day).or_default().push((input, answers));
+    }
+    let pairs: Vec<(&Participant, u8)> = cases
+        .keys()
+        .flat_map(|&day| participants.iter().map(move |p| (p, day)))
+        .collect();
+    let workers = thread::available_parallelism().map_or(1, |n| n.get());
+    println!(
+        "Verifying {} days of {} participants on {workers} threads",
+        cases.len(),
+        participants.len()
+    );
+
+    let next = AtomicUsize::new(0);
+    let outcomes: Mutex<BTreeMap<(String, u8), DayOutcome>> = Mutex::new(BTreeMap::new());
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                while let Some(&(participant, day)) =
+                    pairs.get(next.fetch_add(1, Ordering::Relaxed))
+                {
+                    let outcome = check_day(participant, day, &cases[&day], options);
+                    outcomes
+                        .lock()
+                        .unwrap()
+                        .insert((participant.name.clone(), day), outcome);
+                }
+            });
+        }
+    });
+    let outcomes = outcomes.into_inner().unwrap();
+
+    let mut users: Vec<&str> = participants.iter().map(|p| p.name.as_str()).collect();
+    users.extend(build_failed.iter().map(|name| name.as_str()));
+    users.sort();
+    let mut grid = vec![[
+        vec!["Day".to_string()],
+        users.iter().map(|u| u.to_string()).collect(),
+    ]
+    .concat()];
+    for &day in cases.keys() {
+        let mut row = vec![day.to_string()];
+        for &user in &users {
+            row.push(match outcomes.get(&(user.to_string(), day)) {
+                Some(outcome) => outcome.cell(),
+                None => "🚧".to_string(),
+            });
+        }
+        grid.push(row);
+    }
+    println!();
+    print_grid(&grid);
+
+    let failed: Vec<&String> = outcomes
+        .values()
+        .flat_map(|outcome| &outcome.details)
+        .collect();
+    if !failed.is_empty() {
+        println!();
+        for details in &failed {
+            println!("{details}");
+        }
+    }
+    for name in build_failed {
+        println!("{name}: build failed");
+    }
+    if !failed.is_empty() || !build_failed.is_empty() {
+        return Err(eyre!(
+            "{} inputs and {} builds failed",
+            failed.len(),
+            build_failed.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Runs the same checks as a benchmark run on every input of a day, without benchmarking.
+fn check_day(
+    participant: &Participant,
+    day: u8,
+    cases: &[(String, &ExpectedAnswers)],
+    options: &BenchOptions,
+) -> DayOutcome {
+    let mut outcome = DayOutcome {
+        inputs: cases.len(),
+        ..Default::default()
+    };
+    for (input, answers) in cases {
+        let case = TestCase {
+            day,
+            name: &answers.name,
+            input,
+            answers,
+        };
+        let (parse, part1, part2) =
+            participant
+                .day(day)
+                .bench(&participant.name, &case, options, false);
+        if let (
+            Err(ExecutionError::NotImplemented),
+            Err(ExecutionError::NotImplemented),
+            Err(ExecutionError::NotImplemented),
+        ) = (&parse, &part1, &part2)
+        {
+            outcome.not_implemented = true;
+            return outcome;
+        }
+        let failure = [
+            (Phase::Parse, parse),
+            (Phase::Part1, part1),
+            (Phase::Part2, part2),
+        ]
+        .into_iter()
+        .find_map(|(phase, result)| result.err().map(|e| (phase, e)));
+        if let Some((phase, e)) = failure {
+            outcome.failures.push(e.status());
+            let details = e.details().unwrap_or_else(|| e.status().to_string());
+            outcome.details.push(format!(
+                "{} {} {phase}: {details}",
+                participant.name, answers.name
+            ));
+        }
+    }
+    outcome
+}
+
+/// Prints the rows with every column padded to its widest cell. The emoji take up two columns
+/// of the terminal, so the cells are padded by their display width rather than their length.
+fn print_grid(rows: &[Vec<String>]) {
+    let columns = rows[0].len();
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| rows.iter().map(|row| row[i].width()).max().unwrap_or(0))
+        .collect();
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{cell}{}", " ".repeat(width - cell.width())))
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
+}
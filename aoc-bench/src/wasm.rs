@@ -0,0 +1,205 @@
+//! Runs the WASM programs built by `build-plugins --wasm` in an embedded wasmtime with fuel
+//! metering. The fuel a phase consumes is roughly the number of WASM instructions it executes,
+//! and unlike its time it is the same on every machine and in every run.
+//!
+//! wasmtime is a large dependency, so this is only available with the `wasm` feature.
+
+#[cfg(not(feature = "wasm"))]
+pub use disabled::Runtime;
+
+#[cfg(not(feature = "wasm"))]
+mod disabled {
+    use std::{collections::BTreeMap, path::Path};
+
+    use aoc_bench_results::Phase;
+    use color_eyre::eyre::{eyre, Result};
+
+    use crate::answers::ExpectedAnswers;
+
+    pub struct Runtime;
+
+    impl Runtime {
+        pub fn load(_dir: &Path, _fuel_limit: u64) -> Result<Self> {
+            Err(eyre!("aoc-bench was built without the wasm feature"))
+        }
+
+        pub fn fuel(
+            &self,
+            _user: &str,
+            _day: u8,
+            _input: &str,
+            _answers: &ExpectedAnswers,
+        ) -> Result<BTreeMap<Phase, u64>> {
+            unreachable!("the runtime cannot be loaded")
+        }
+    }
+}
+
+#[cfg(feature = "wasm")]
+pub use runtime::Runtime;
+
+#[cfg(feature = "wasm")]
+mod runtime {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        path::Path,
+    };
+
+    use aoc_bench_results::Phase;
+    use color_eyre::eyre::{eyre, Context, Result};
+    use wasmtime::{Caller, Config, Engine, Linker, Module, Store};
+    use wasmtime_wasi::{
+        pipe::{MemoryInputPipe, MemoryOutputPipe},
+        preview1::{self, WasiP1Ctx},
+        WasiCtxBuilder,
+    };
+
+    use crate::{answers::ExpectedAnswers, builder::WASM_ANSWER_MARKER};
+
+    /// The most output a program may write to stdout or stderr.
+    const OUTPUT_CAPACITY: usize = 1 << 20;
+
+    struct State {
+        wasi: WasiP1Ctx,
+        /// the fuel left when the current phase started
+        start: u64,
+        fuel: BTreeMap<Phase, u64>,
+    }
+
+    /// The compiled WASM programs of all participants.
+    pub struct Runtime {
+        engine: Engine,
+        linker: Linker<State>,
+        modules: HashMap<String, Module>,
+        fuel_limit: u64,
+    }
+
+    impl Runtime {
+        /// Compiles the programs in `dir`, named `<participant>.wasm`. A run is aborted once it
+        /// has consumed `fuel_limit`.
+        pub fn load(dir: &Path, fuel_limit: u64) -> Result<Self> {
+            let mut config = Config::new();
+            config.consume_fuel(true);
+            let engine = Engine::new(&config).map_err(|e| eyre!(e))?;
+            let mut linker = Linker::new(&engine);
+            preview1::add_to_linker_sync(&mut linker, |state: &mut State| &mut state.wasi)
+                .map_err(|e| eyre!(e))?;
+            linker
+                .func_wrap(
+                    "aoc_bench",
+                    "phase_start",
+                    |mut caller: Caller<'_, State>| -> wasmtime::Result<()> {
+                        caller.data_mut().start = caller.get_fuel()?;
+                        Ok(())
+                    },
+                )
+                .map_err(|e| eyre!(e))?;
+            linker
+                .func_wrap(
+                    "aoc_bench",
+                    "phase_end",
+                    |mut caller: Caller<'_, State>, phase: u32| -> wasmtime::Result<()> {
+                        let left = caller.get_fuel()?;
+                        let state = caller.data_mut();
+                        let phase = match phase {
+                            0 => Phase::Parse,
+                            1 => Phase::Part1,
+                            _ => Phase::Part2,
+                        };
+                        state.fuel.insert(phase, state.start - left);
+                        Ok(())
+                    },
+                )
+                .map_err(|e| eyre!(e))?;
+
+            let mut modules = HashMap::new();
+            let entries = std::fs::read_dir(dir)
+                .with_context(|| format!("cannot read plugin dir {}", dir.display()))?;
+            for path in entries.filter_map(|entry| entry.ok().map(|e| e.path())) {
+                if path.extension().and_then(|e| e.to_str()) != Some("wasm") {
+                    continue;
+                }
+                let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                let module = Module::from_file(&engine, &path)
+                    .map_err(|e| eyre!("cannot compile {}: {e}", path.display()))?;
+                modules.insert(name.to_string(), module);
+            }
+            Ok(Runtime {
+                engine,
+                linker,
+                modules,
+                fuel_limit,
+            })
+        }
+
+        /// Runs a day of `user` on `input` and returns the fuel consumed by every phase, if both
+        /// answers are right.
+        pub fn fuel(
+            &self,
+            user: &str,
+            day: u8,
+            input: &str,
+            answers: &ExpectedAnswers,
+        ) -> Result<BTreeMap<Phase, u64>> {
+            let module = self
+                .modules
+                .get(user)
+                .ok_or_else(|| eyre!("there is no WASM program of {user}"))?;
+            let stdout = MemoryOutputPipe::new(OUTPUT_CAPACITY);
+            let stderr = MemoryOutputPipe::new(OUTPUT_CAPACITY);
+            let wasi = WasiCtxBuilder::new()
+                .stdin(MemoryInputPipe::new(input.to_string()))
+                .stdout(stdout.clone())
+                .stderr(stderr.clone())
+                .args(&["solutions".to_string(), day.to_string()])
+                .build_p1();
+            let mut store = Store::new(
+                &self.engine,
+                State {
+                    wasi,
+                    start: 0,
+                    fuel: BTreeMap::new(),
+                },
+            );
+            store.set_fuel(self.fuel_limit).map_err(|e| eyre!(e))?;
+            let run = self
+                .linker
+                .instantiate(&mut store, module)
+                .and_then(|instance| instance.get_typed_func::<(), ()>(&mut store, "_start"))
+                .and_then(|start| start.call(&mut store, ()));
+            if let Err(e) = run {
+                let stderr = String::from_utf8_lossy(&stderr.contents()).into_owned();
+                return Err(match e.downcast_ref::<wasmtime::Trap>() {
+                    Some(wasmtime::Trap::OutOfFuel) => {
+                        eyre!("ran out of fuel after {}", self.fuel_limit)
+                    }
+                    _ => eyre!("{e} {}", stderr.trim()),
+                });
+            }
+            let stdout = String::from_utf8_lossy(&stdout.contents()).into_owned();
+            let (part1, part2) =
+                answers_of(&stdout).ok_or_else(|| eyre!("the program printed no answers"))?;
+            if !answers.check_part1(part1) {
+                return Err(eyre!("wrong answer {part1:?} for part1"));
+            }
+            if !answers.check_part2(part2) {
+                return Err(eyre!("wrong answer {part2:?} for part2"));
+            }
+            let mut fuel = store.into_data().fuel;
+            let total = fuel.values().sum();
+            fuel.insert(Phase::Total, total);
+            Ok(fuel)
+        }
+    }
+
+    /// The answers after the last two marker lines of the output of a program, anything the
+    /// solutions printed comes before them.
+    fn answers_of(stdout: &str) -> Option<(&str, &str)> {
+        let marker = format!("\n{WASM_ANSWER_MARKER}\n");
+        let (rest, part2) = stdout.rsplit_once(&marker)?;
+        let (_, part1) = rest.rsplit_once(&marker)?;
+        Some((part1, part2.strip_suffix('\n').unwrap_or(part2)))
+    }
+}
@@ -0,0 +1,278 @@
+//! A stable C ABI for [`AdventOfCodeSolutions`], so solutions can be built as a `cdylib`
+//! and loaded by the benchmark harness at runtime.
+//!
+//! Use [`export_solutions!`](crate::export_solutions) in a crate with
+//! `crate-type = ["cdylib"]` to export the entry point:
+//!
+//! ```ignore
+//! aoc_traits::export_solutions!(meta::AoC2024);
+//! ```
+//!
+//! All panics are caught inside the plugin and reported as [`FfiStatus::Panic`] together
+//! with the panic message, they never unwind across the ABI boundary.
+
+use std::{
+    any::{Any, TypeId},
+    ffi::c_void,
+    hint::black_box,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::{AdventOfCodeDay, AdventOfCodeSolutions};
+
+/// The version of the ABI, bumped on every incompatible change of the types in this module.
+pub const ABI_VERSION: u32 = 3;
+
+/// The name of the symbol exported by [`export_solutions!`](crate::export_solutions). It stays
+/// the same across ABI versions, so a plugin built for another version still loads and is
+/// rejected by its [`FfiSolutions::abi_version`] with a clear message.
+pub const ENTRY_POINT: &[u8] = b"aoc_solutions\0";
+
+/// The signature of the entry point exported by [`export_solutions!`](crate::export_solutions).
+pub type EntryPoint = unsafe extern "C" fn() -> *const FfiSolutions;
+
+/// An owned UTF-8 string allocated by the plugin.
+/// It has to be given back to the plugin via [`FfiSolutions::free_buffer`] to release it.
+#[repr(C)]
+pub struct FfiBuffer {
+    pub ptr: *mut u8,
+    pub len: usize,
+    pub cap: usize,
+}
+
+impl FfiBuffer {
+    pub const fn empty() -> Self {
+        FfiBuffer {
+            ptr: std::ptr::null_mut(),
+            len: 0,
+            cap: 0,
+        }
+    }
+
+    /// Views the buffer as a string.
+    ///
+    /// # Safety
+    /// The buffer must have been filled in by one of the functions of a [`FfiDay`].
+    pub unsafe fn as_str(&self) -> &str {
+        if self.ptr.is_null() {
+            return "";
+        }
+        std::str::from_utf8_unchecked(std::slice::from_raw_parts(self.ptr, self.len))
+    }
+}
+
+impl From<String> for FfiBuffer {
+    fn from(s: String) -> Self {
+        let mut s = std::mem::ManuallyDrop::new(s);
+        FfiBuffer {
+            ptr: s.as_mut_ptr(),
+            len: s.len(),
+            cap: s.capacity(),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FfiStatus {
+    /// The call succeeded, the output buffer contains the answer (if any).
+    Ok = 0,
+    /// The call panicked, the output buffer contains the panic message.
+    Panic = 1,
+}
+
+/// The type-erased functions of a single [`AdventOfCodeDay`].
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct FfiDay {
+    /// `false` if the day is not implemented, i.e., uses the default `()` impl.
+    pub implemented: bool,
+    /// Parses the input into an opaque handle written to `parsed`.
+    /// The handle may borrow from `input`, so `input` has to outlive it. `out` is only
+    /// written to if the parser panicked.
+    pub parse: unsafe extern "C" fn(
+        input: *const u8,
+        len: usize,
+        parsed: *mut *mut c_void,
+        out: *mut FfiBuffer,
+    ) -> FfiStatus,
+    /// Solves part 1 for a handle created by `parse`, writing the answer to `out`.
+    pub part1: unsafe extern "C" fn(parsed: *const c_void, out: *mut FfiBuffer) -> FfiStatus,
+    /// Solves part 2 for a handle created by `parse`, writing the answer to `out`.
+    pub part2: unsafe extern "C" fn(parsed: *const c_void, out: *mut FfiBuffer) -> FfiStatus,
+    /// Solves part 1 like `part1`, but only passes the answer to `black_box` instead of
+    /// formatting it, so the benchmarks measure nothing but the solution. `out` is only
+    /// written to on a panic.
+    pub bench_part1: unsafe extern "C" fn(parsed: *const c_void, out: *mut FfiBuffer) -> FfiStatus,
+    /// Like `bench_part1`, for part 2.
+    pub bench_part2: unsafe extern "C" fn(parsed: *const c_void, out: *mut FfiBuffer) -> FfiStatus,
+    /// Releases a handle created by `parse`. `out` is only written to if the drop panicked.
+    pub drop_parsed: unsafe extern "C" fn(parsed: *mut c_void, out: *mut FfiBuffer) -> FfiStatus,
+}
+
+/// The table returned by the entry point of a solutions plugin.
+#[repr(C)]
+pub struct FfiSolutions {
+    /// Has to be equal to [`ABI_VERSION`].
+    pub abi_version: u32,
+    pub days: [FfiDay; 25],
+    /// Releases a buffer returned by one of the functions in `days`.
+    pub free_buffer: unsafe extern "C" fn(buffer: FfiBuffer),
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+fn call(out: *mut FfiBuffer, f: impl FnOnce() -> String) -> FfiStatus {
+    let (status, message) = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(s) => (FfiStatus::Ok, s),
+        Err(e) => (FfiStatus::Panic, panic_message(&*e)),
+    };
+    // SAFETY: the caller passes a valid pointer to write the output to
+    unsafe { out.write(message.into()) };
+    status
+}
+
+/// Like [`call`], but without an output on success, so nothing is allocated.
+fn call_discarding(out: *mut FfiBuffer, f: impl FnOnce()) -> FfiStatus {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(()) => FfiStatus::Ok,
+        Err(e) => {
+            // SAFETY: the caller passes a valid pointer to write the output to
+            unsafe { out.write(panic_message(&*e).into()) };
+            FfiStatus::Panic
+        }
+    }
+}
+
+unsafe extern "C" fn parse<D: AdventOfCodeDay>(
+    input: *const u8,
+    len: usize,
+    parsed: *mut *mut c_void,
+    out: *mut FfiBuffer,
+) -> FfiStatus {
+    let input = std::str::from_utf8_unchecked(std::slice::from_raw_parts(input, len));
+    call_discarding(out, || {
+        let p = Box::new(D::parse_input(input));
+        parsed.write(Box::into_raw(p).cast());
+    })
+}
+
+unsafe extern "C" fn part1<D: AdventOfCodeDay>(
+    parsed: *const c_void,
+    out: *mut FfiBuffer,
+) -> FfiStatus {
+    let parsed = &*parsed.cast::<D::ParsedInput<'_>>();
+    call(out, || D::solve_part1(parsed).to_string())
+}
+
+unsafe extern "C" fn part2<D: AdventOfCodeDay>(
+    parsed: *const c_void,
+    out: *mut FfiBuffer,
+) -> FfiStatus {
+    let parsed = &*parsed.cast::<D::ParsedInput<'_>>();
+    call(out, || D::solve_part2(parsed).to_string())
+}
+
+unsafe extern "C" fn bench_part1<D: AdventOfCodeDay>(
+    parsed: *const c_void,
+    out: *mut FfiBuffer,
+) -> FfiStatus {
+    let parsed = &*parsed.cast::<D::ParsedInput<'_>>();
+    call_discarding(out, || {
+        black_box(D::solve_part1(parsed));
+    })
+}
+
+unsafe extern "C" fn bench_part2<D: AdventOfCodeDay>(
+    parsed: *const c_void,
+    out: *mut FfiBuffer,
+) -> FfiStatus {
+    let parsed = &*parsed.cast::<D::ParsedInput<'_>>();
+    call_discarding(out, || {
+        black_box(D::solve_part2(parsed));
+    })
+}
+
+unsafe extern "C" fn drop_parsed<D: AdventOfCodeDay>(
+    parsed: *mut c_void,
+    out: *mut FfiBuffer,
+) -> FfiStatus {
+    let parsed = Box::from_raw(parsed.cast::<D::ParsedInput<'_>>());
+    call_discarding(out, || drop(parsed))
+}
+
+unsafe extern "C" fn free_buffer(buffer: FfiBuffer) {
+    if !buffer.ptr.is_null() {
+        drop(String::from_raw_parts(buffer.ptr, buffer.len, buffer.cap));
+    }
+}
+
+/// Builds the function table for a single day.
+pub fn day<D: AdventOfCodeDay + 'static>() -> FfiDay {
+    FfiDay {
+        implemented: TypeId::of::<D>() != TypeId::of::<()>(),
+        parse: parse::<D>,
+        part1: part1::<D>,
+        part2: part2::<D>,
+        bench_part1: bench_part1::<D>,
+        bench_part2: bench_part2::<D>,
+        drop_parsed: drop_parsed::<D>,
+    }
+}
+
+/// Builds the function table for all days, used by [`export_solutions!`](crate::export_solutions).
+pub fn solutions<S: AdventOfCodeSolutions + 'static>() -> FfiSolutions {
+    FfiSolutions {
+        abi_version: ABI_VERSION,
+        days: [
+            day::<S::Day01>(),
+            day::<S::Day02>(),
+            day::<S::Day03>(),
+            day::<S::Day04>(),
+            day::<S::Day05>(),
+            day::<S::Day06>(),
+            day::<S::Day07>(),
+            day::<S::Day08>(),
+            day::<S::Day09>(),
+            day::<S::Day10>(),
+            day::<S::Day11>(),
+            day::<S::Day12>(),
+            day::<S::Day13>(),
+            day::<S::Day14>(),
+            day::<S::Day15>(),
+            day::<S::Day16>(),
+            day::<S::Day17>(),
+            day::<S::Day18>(),
+            day::<S::Day19>(),
+            day::<S::Day20>(),
+            day::<S::Day21>(),
+            day::<S::Day22>(),
+            day::<S::Day23>(),
+            day::<S::Day24>(),
+            day::<S::Day25>(),
+        ],
+        free_buffer,
+    }
+}
+
+/// Exports the C ABI entry point for a type implementing [`AdventOfCodeSolutions`].
+/// The crate invoking this has to be built with `crate-type = ["cdylib"]`.
+#[macro_export]
+macro_rules! export_solutions {
+    ($solutions:ty) => {
+        #[no_mangle]
+        pub extern "C" fn aoc_solutions() -> *const $crate::ffi::FfiSolutions {
+            static SOLUTIONS: ::std::sync::OnceLock<$crate::ffi::FfiSolutions> =
+                ::std::sync::OnceLock::new();
+            SOLUTIONS.get_or_init($crate::ffi::solutions::<$solutions>)
+        }
+    };
+}
@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+pub mod ffi;
+
 /// A somewhat unified interface for the Advent of Code problems.
 pub trait AdventOfCodeDay {
     /// The result of parsing your input, can be whatever you want to store the parsed input as.
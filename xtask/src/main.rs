@@ -0,0 +1,48 @@
+//! Maintenance tasks of the workspace, run with `cargo xtask <task>`.
+//!
+//! - `sync-participants`: regenerates the participant dependencies and the
+//!   `static-participants` feature in `aoc-bench/Cargo.toml` from `participants.toml`. The build
+//!   of aoc-bench fails until they are in sync, so run it after editing `participants.toml`.
+
+use std::{path::Path, process::ExitCode};
+
+#[path = "../../aoc-bench/manifest.rs"]
+mod manifest;
+#[allow(dead_code)]
+#[path = "../../aoc-bench/src/participants.rs"]
+mod participants;
+
+fn sync_participants() -> Result<(), String> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask is part of the workspace");
+    let manifest_dir = root.join("aoc-bench");
+    let cargo_toml_path = manifest_dir.join("Cargo.toml");
+    let participants = participants::load(&root.join("participants.toml"))?;
+    let cargo_toml = std::fs::read_to_string(&cargo_toml_path)
+        .map_err(|e| format!("cannot read {}: {e}", cargo_toml_path.display()))?;
+    match manifest::sync(&cargo_toml, &participants, root, &manifest_dir)? {
+        Some(updated) => {
+            std::fs::write(&cargo_toml_path, updated)
+                .map_err(|e| format!("cannot write {}: {e}", cargo_toml_path.display()))?;
+            println!("Updated {}", cargo_toml_path.display());
+        }
+        None => println!("{} is up to date", cargo_toml_path.display()),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let task = std::env::args().nth(1);
+    let result = match task.as_deref() {
+        Some("sync-participants") => sync_participants(),
+        _ => Err("usage: cargo xtask sync-participants".to_string()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}